@@ -0,0 +1,317 @@
+use crate::save::Settings;
+use crate::defs::Explosion;
+use crate::synth::{Envelope, Voice, Wave};
+use crate::world::GameEvent;
+// Głośność muzyki względem efektów
+const MUSIC_VOLUME: f32 = 0.5;
+// Ile efektów może grać jednocześnie, w tym ile kopii tego samego dźwięku
+const MAX_VOICES: usize = 8;
+const MAX_SAME_VOICES: usize = 3;
+// Głośność muzyki podczas przyciszenia
+const DUCK_LEVEL: f32 = 0.3;
+// Efekty dźwiękowe
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sfx {
+    PlayerShot,
+    EnemyShot,
+    Hit,
+    Kill(Explosion),
+    PowerUp,
+    PlayerDamage,
+}
+impl Sfx {
+    pub fn for_event(event: GameEvent) -> Sfx {
+        match event {
+            GameEvent::PlayerShot => Sfx::PlayerShot,
+            GameEvent::EnemyShot => Sfx::EnemyShot,
+            GameEvent::EnemyHit => Sfx::Hit,
+            GameEvent::EnemyKilled(_, explosion) => Sfx::Kill(explosion),
+            GameEvent::PowerUpCollected(_) => Sfx::PowerUp,
+            GameEvent::PlayerDamaged => Sfx::PlayerDamage,
+        }
+    }
+    // Parametry syntezatora dla każdego efektu
+    pub fn voice(self) -> Voice {
+        let (wave, start_hz, end_hz, attack, sustain, decay, volume) = match self {
+            Sfx::PlayerShot => (Wave::Square { duty: 0.25 }, 1_200.0, 300.0, 0.0, 0.02, 0.08, 0.4),
+            Sfx::EnemyShot => (Wave::Square { duty: 0.5 }, 500.0, 250.0, 0.0, 0.03, 0.1, 0.35),
+            Sfx::Hit => (Wave::Noise, 3_000.0, 1_500.0, 0.0, 0.01, 0.06, 0.5),
+            // Eksplozje: szum z opadającą wysokością, większy wróg - niższy i dłuższy huk
+            Sfx::Kill(Explosion::Small) => (Wave::Noise, 1_500.0, 200.0, 0.0, 0.05, 0.25, 0.7),
+            Sfx::Kill(Explosion::Sharp) => (Wave::Noise, 2_500.0, 400.0, 0.0, 0.03, 0.18, 0.6),
+            Sfx::Kill(Explosion::Heavy) => (Wave::Noise, 800.0, 60.0, 0.0, 0.1, 0.45, 0.9),
+            Sfx::Kill(Explosion::Medium) => (Wave::Noise, 1_200.0, 120.0, 0.0, 0.06, 0.3, 0.75),
+            Sfx::PowerUp => (Wave::Square { duty: 0.5 }, 400.0, 1_600.0, 0.01, 0.1, 0.2, 0.45),
+            Sfx::PlayerDamage => (Wave::Noise, 600.0, 80.0, 0.0, 0.15, 0.35, 0.9),
+        };
+        Voice { wave, start_hz, end_hz, envelope: Envelope { attack, sustain, decay }, volume }
+    }
+}
+// Urządzenie odtwarzające; głośność jest już przeskalowana ustawieniami
+pub trait AudioBackend {
+    fn play(&mut self, sfx: Sfx, volume: f32);
+    // Wywoływane co klatkę - backend sam pilnuje, czy coś się zmieniło
+    fn set_music(&mut self, playing: bool, volume: f32);
+}
+// Brak dźwięku (gra bez karty dźwiękowej, testy, build bez feature "audio")
+pub struct Silent;
+impl AudioBackend for Silent {
+    fn play(&mut self, _sfx: Sfx, _volume: f32) {}
+    fn set_music(&mut self, _playing: bool, _volume: f32) {}
+}
+// Mikser: pilnuje limitu jednocześnie grających efektów i przycisza muzykę
+pub struct Mixer {
+    time: f32,
+    voices: Vec<(Sfx, f32)>, // efekt i czas jego końca
+    started: Vec<Sfx>, // efekty uruchomione w tej klatce
+    duck_until: f32,
+}
+impl Mixer {
+    pub fn new() -> Mixer {
+        Mixer { time: 0.0, voices: vec![], started: vec![], duck_until: 0.0 }
+    }
+    // Upływ czasu; zwalnia głosy, które już wybrzmiały
+    pub fn advance(&mut self, dt: f32) {
+        self.time += dt;
+        let time = self.time;
+        self.voices.retain(|&(_, end)| end > time);
+        self.started.clear();
+    }
+    // Czy efekt może zagrać - ten sam dźwięk startuje najwyżej raz na klatkę
+    pub fn admit(&mut self, sfx: Sfx, duration: f32) -> bool {
+        let same = self.voices.iter().filter(|(s, _)| *s == sfx).count();
+        if self.started.contains(&sfx) || same >= MAX_SAME_VOICES || self.voices.len() >= MAX_VOICES {
+            return false;
+        }
+        self.started.push(sfx);
+        self.voices.push((sfx, self.time + duration));
+        true
+    }
+    pub fn duck(&mut self, seconds: f32) {
+        self.duck_until = self.duck_until.max(self.time + seconds);
+    }
+    // Mnożnik głośności muzyki
+    pub fn music_gain(&self) -> f32 {
+        if self.time < self.duck_until { DUCK_LEVEL } else { 1.0 }
+    }
+}
+// Dźwięk gry sterowany głośnościami z ustawień
+pub struct Audio {
+    backend: Box<dyn AudioBackend>,
+    mixer: Mixer,
+    master: f32,
+    music: f32,
+    effects: f32,
+    muted: bool,
+}
+impl Audio {
+    pub fn new(backend: Box<dyn AudioBackend>, settings: &Settings) -> Audio {
+        let mut audio = Audio { backend, mixer: Mixer::new(), master: 0.0, music: 0.0, effects: 0.0, muted: false };
+        audio.configure(settings);
+        audio
+    }
+    pub fn configure(&mut self, settings: &Settings) {
+        self.master = settings.master_volume.clamp(0.0, 1.0);
+        self.music = settings.music_volume.clamp(0.0, 1.0);
+        self.effects = settings.sfx_volume.clamp(0.0, 1.0);
+        self.muted = settings.muted;
+    }
+    fn effects_gain(&self) -> f32 {
+        if self.muted { 0.0 } else { self.master * self.effects }
+    }
+    fn music_gain(&self) -> f32 {
+        if self.muted { 0.0 } else { self.master * self.music * MUSIC_VOLUME * self.mixer.music_gain() }
+    }
+    pub fn play(&mut self, sfx: Sfx) {
+        let gain = self.effects_gain();
+        if gain > 0.0 && self.mixer.admit(sfx, sfx.voice().envelope.duration()) {
+            self.backend.play(sfx, gain);
+        }
+    }
+    pub fn play_events(&mut self, events: &[GameEvent]) {
+        for &event in events {
+            self.play(Sfx::for_event(event));
+        }
+    }
+    // Przyciszenie muzyki na czas dużego zdarzenia (np. śmierci gracza)
+    pub fn duck(&mut self, seconds: f32) {
+        self.mixer.duck(seconds);
+    }
+    // Wywoływane raz na klatkę
+    pub fn update(&mut self, dt: f32, music: bool) {
+        self.mixer.advance(dt);
+        let gain = self.music_gain();
+        self.backend.set_music(music && gain > 0.0, gain);
+    }
+}
+// Ładuje dźwięki do macroquada; bez urządzenia wyjściowego, przy błędzie ładowania albo w buildzie
+// bez feature "audio" gra działa po cichu
+pub async fn open_audio(settings: &Settings) -> Audio {
+    #[cfg(feature = "audio")]
+    if let Some(speakers) = device::Speakers::open().await {
+        return Audio::new(Box::new(speakers), settings);
+    }
+    Audio::new(Box::new(Silent), settings)
+}
+// Odtwarzanie przez macroquad; dźwięki generuje syntezator przy starcie
+#[cfg(feature = "audio")]
+mod device {
+    use super::{AudioBackend, Sfx};
+    use crate::defs::Explosion;
+    use crate::synth::{synthesize, to_pcm, wav, Envelope, Voice, Wave};
+    use macroquad::audio::{load_sound_from_bytes, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound};
+    const EFFECTS: [Sfx; 9] = [
+        Sfx::PlayerShot,
+        Sfx::EnemyShot,
+        Sfx::Hit,
+        Sfx::Kill(Explosion::Small),
+        Sfx::Kill(Explosion::Sharp),
+        Sfx::Kill(Explosion::Heavy),
+        Sfx::Kill(Explosion::Medium),
+        Sfx::PowerUp,
+        Sfx::PlayerDamage,
+    ];
+    pub struct Speakers {
+        effects: Vec<(Sfx, Sound)>,
+        music: Sound,
+        music_playing: bool,
+        music_volume: f32,
+    }
+    impl Speakers {
+        pub async fn open() -> Option<Speakers> {
+            if !output_available() {
+                return None;
+            }
+            let mut effects = vec![];
+            for sfx in EFFECTS {
+                let sound = load_sound_from_bytes(&load(&synthesize(&sfx.voice()))).await.ok()?;
+                effects.push((sfx, sound));
+            }
+            let music = load_sound_from_bytes(&load(&music())).await.ok()?;
+            Some(Speakers { effects, music, music_playing: false, music_volume: 0.0 })
+        }
+    }
+    impl AudioBackend for Speakers {
+        fn play(&mut self, sfx: Sfx, volume: f32) {
+            if let Some((_, sound)) = self.effects.iter().find(|(s, _)| *s == sfx) {
+                play_sound(sound, PlaySoundParams { looped: false, volume });
+            }
+        }
+        fn set_music(&mut self, playing: bool, volume: f32) {
+            if playing != self.music_playing {
+                if playing {
+                    play_sound(&self.music, PlaySoundParams { looped: true, volume });
+                } else {
+                    stop_sound(&self.music);
+                }
+                self.music_playing = playing;
+                self.music_volume = volume;
+            } else if playing && volume != self.music_volume {
+                set_sound_volume(&self.music, volume);
+                self.music_volume = volume;
+            }
+        }
+    }
+    // Wątek dźwięku macroquada bez urządzenia po prostu się kończy, a każdy kolejny dźwięk wypisuje
+    // błąd - dlatego urządzenie jest sprawdzane wcześniej, pod tymi samymi nazwami co w quad-snd
+    #[cfg(target_os = "linux")]
+    fn output_available() -> bool {
+        use quad_alsa_sys as alsa;
+        ["default\0", "pipewire\0"].iter().any(|device| unsafe {
+            let mut pcm = std::ptr::null_mut();
+            let opened = alsa::snd_pcm_open(&mut pcm, device.as_ptr() as _, alsa::SND_PCM_STREAM_PLAYBACK, alsa::SND_PCM_NONBLOCK) >= 0;
+            if opened {
+                alsa::snd_pcm_close(pcm);
+            }
+            opened
+        })
+    }
+    #[cfg(not(target_os = "linux"))]
+    fn output_available() -> bool {
+        true
+    }
+    fn load(samples: &[f32]) -> Vec<u8> {
+        wav(&to_pcm(samples))
+    }
+    // Zapętlone arpeggio: a-moll, F, C, G
+    fn music() -> Vec<f32> {
+        const ROOTS: [f32; 4] = [110.0, 87.31, 130.81, 98.0];
+        const STEPS: [f32; 4] = [1.0, 1.5, 2.0, 1.5];
+        let envelope = Envelope { attack: 0.01, sustain: 0.1, decay: 0.14 };
+        let mut samples = vec![];
+        for root in ROOTS {
+            for step in 0..16 {
+                let hz = root * STEPS[step % 4];
+                samples.extend(synthesize(&Voice { wave: Wave::Triangle, start_hz: hz, end_hz: hz, envelope, volume: 0.4 }));
+            }
+        }
+        samples
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kinds::EnemyKind;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // Backend zapamiętujący odtworzone dźwięki
+    struct Recorder(Rc<RefCell<Vec<(Sfx, f32)>>>);
+    impl AudioBackend for Recorder {
+        fn play(&mut self, sfx: Sfx, volume: f32) {
+            self.0.borrow_mut().push((sfx, volume));
+        }
+        fn set_music(&mut self, _playing: bool, _volume: f32) {}
+    }
+
+    fn settings(master: f32, sfx: f32) -> Settings {
+        Settings { master_volume: master, sfx_volume: sfx, ..Settings::default() }
+    }
+
+    #[test]
+    fn events_are_played_at_settings_volume() {
+        let played = Rc::new(RefCell::new(vec![]));
+        let mut audio = Audio::new(Box::new(Recorder(played.clone())), &settings(0.5, 0.6));
+        audio.play_events(&[GameEvent::PlayerShot, GameEvent::EnemyKilled(EnemyKind(2), Explosion::Heavy)]);
+        assert_eq!(*played.borrow(), vec![(Sfx::PlayerShot, 0.3), (Sfx::Kill(Explosion::Heavy), 0.3)]);
+        // Wyciszenie blokuje wszystkie efekty
+        audio.configure(&Settings { muted: true, ..settings(0.5, 0.6) });
+        audio.update(1.0, true);
+        audio.play(Sfx::Hit);
+        assert_eq!(played.borrow().len(), 2);
+    }
+
+    #[test]
+    fn mixer_limits_voices() {
+        let mut mixer = Mixer::new();
+        assert!(mixer.admit(Sfx::PlayerShot, 0.1));
+        // Ten sam dźwięk drugi raz w tej samej klatce
+        assert!(!mixer.admit(Sfx::PlayerShot, 0.1));
+        for _ in 0..5 {
+            mixer.advance(0.01);
+            mixer.admit(Sfx::PlayerShot, 0.1);
+        }
+        assert_eq!(mixer.voices.len(), MAX_SAME_VOICES);
+        for explosion in [Explosion::Small, Explosion::Sharp, Explosion::Heavy, Explosion::Medium] {
+            mixer.admit(Sfx::Kill(explosion), 1.0);
+        }
+        mixer.admit(Sfx::Hit, 1.0);
+        assert!(!mixer.admit(Sfx::PowerUp, 1.0));
+        assert_eq!(mixer.voices.len(), MAX_VOICES);
+        // Po wybrzmieniu miejsce się zwalnia
+        mixer.advance(1.0);
+        assert!(mixer.voices.is_empty());
+        assert!(mixer.admit(Sfx::PowerUp, 1.0));
+    }
+
+    #[test]
+    fn ducking_lowers_music_for_a_while() {
+        let mut mixer = Mixer::new();
+        mixer.duck(2.0);
+        assert_eq!(mixer.music_gain(), DUCK_LEVEL);
+        mixer.advance(1.5);
+        assert_eq!(mixer.music_gain(), DUCK_LEVEL);
+        mixer.advance(1.0);
+        assert_eq!(mixer.music_gain(), 1.0);
+    }
+}
@@ -0,0 +1,87 @@
+use macroquad::prelude::*;
+// Logiczny rozmiar planszy; gra, interfejs i zapisy używają tych jednostek niezależnie od okna
+pub const PLAYFIELD: Vec2 = Vec2::new(480.0, 640.0);
+// Dopasowanie obszaru logicznego do większego/mniejszego obszaru z zachowaniem proporcji
+// (czarne pasy po bokach albo u góry i dołu)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Viewport {
+    pub offset: Vec2, // lewy górny róg obszaru logicznego w pikselach docelowych
+    pub scale: f32,   // pikseli docelowych na jednostkę logiczną
+}
+impl Viewport {
+    pub fn fit(target: Vec2, logical: Vec2) -> Viewport {
+        let scale = (target.x / logical.x).min(target.y / logical.y);
+        Viewport { offset: (target - logical * scale) / 2.0, scale }
+    }
+    // Punkt docelowy (np. pozycja myszy w oknie) w jednostkach logicznych
+    pub fn to_logical(self, point: Vec2) -> Vec2 {
+        (point - self.offset) / self.scale
+    }
+}
+// Płótno o stałym logicznym rozmiarze, skalowane do okna przy wyświetlaniu
+pub struct Canvas {
+    target: RenderTarget,
+}
+impl Canvas {
+    pub fn new() -> Canvas {
+        let target = render_target(PLAYFIELD.x as u32, PLAYFIELD.y as u32);
+        target.texture.set_filter(FilterMode::Linear);
+        Canvas { target }
+    }
+    fn viewport() -> Viewport {
+        Viewport::fit(vec2(screen_width(), screen_height()), PLAYFIELD)
+    }
+    // Pozycja myszy w jednostkach logicznych
+    pub fn mouse() -> Vec2 {
+        Canvas::viewport().to_logical(mouse_position().into())
+    }
+    // Kamera rysująca na płótno podany fragment przestrzeni
+    fn camera(&self, rect: Rect) {
+        set_camera(&Camera2D { render_target: Some(self.target.clone()), ..Camera2D::from_display_rect(rect) });
+    }
+    // Początek klatki: rysowanie na czyste płótno w jednostkach logicznych
+    pub fn begin(&self) {
+        self.set_logical();
+        clear_background(BLACK);
+    }
+    pub fn set_logical(&self) {
+        self.camera(Rect::new(0.0, 0.0, PLAYFIELD.x, PLAYFIELD.y));
+    }
+    // Rysowanie areny o innym rozmiarze (stare nagrania) - wpasowana w płótno
+    pub fn set_arena(&self, arena: Vec2) {
+        let fit = Viewport::fit(PLAYFIELD, arena);
+        let origin = fit.to_logical(Vec2::ZERO);
+        self.camera(Rect::new(origin.x, origin.y, PLAYFIELD.x / fit.scale, PLAYFIELD.y / fit.scale));
+    }
+    // Wyświetlenie płótna w oknie z zachowaniem proporcji
+    pub fn present(&self) {
+        set_default_camera();
+        clear_background(BLACK);
+        let viewport = Canvas::viewport();
+        let params = DrawTextureParams { dest_size: Some(PLAYFIELD * viewport.scale), flip_y: true, ..Default::default() };
+        draw_texture_ex(&self.target.texture, viewport.offset.x, viewport.offset.y, WHITE, params);
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_window_gets_side_bars() {
+        let viewport = Viewport::fit(vec2(1280.0, 640.0), PLAYFIELD);
+        assert_eq!(viewport, Viewport { offset: vec2(400.0, 0.0), scale: 1.0 });
+        assert_eq!(viewport.to_logical(vec2(400.0, 0.0)), Vec2::ZERO);
+        assert_eq!(viewport.to_logical(vec2(640.0, 320.0)), vec2(240.0, 320.0));
+    }
+
+    #[test]
+    fn tall_window_scales_and_gets_top_bars() {
+        let viewport = Viewport::fit(vec2(960.0, 2000.0), PLAYFIELD);
+        assert_eq!(viewport.scale, 2.0);
+        assert_eq!(viewport.offset, vec2(0.0, 360.0));
+        // Mysz na pasku jest poza planszą
+        let mouse = viewport.to_logical(vec2(100.0, 100.0));
+        assert!(mouse.y < 0.0);
+        assert_eq!(viewport.to_logical(vec2(960.0, 1640.0)), PLAYFIELD);
+    }
+}
@@ -0,0 +1,173 @@
+use macroquad::prelude::{vec2, Vec2};
+use std::collections::HashMap;
+// Kształty kolizji zgodne z tym, co jest rysowane (domyślne rozmiary rodzajów)
+pub const PLAYER_SIZE: Vec2 = Vec2::new(20.0, 20.0);
+pub const ENEMY_SIZE: Vec2 = Vec2::new(20.0, 20.0);
+pub const BULLET_RADIUS: f32 = 5.0;
+pub const POWER_UP_RADIUS: f32 = 10.0;
+// Kształt do testów trafień; samo zetknięcie krawędzi nie jest trafieniem
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Shape {
+    Aabb { min: Vec2, size: Vec2 }, // prostokąt od lewego górnego rogu
+    Circle { center: Vec2, radius: f32 },
+}
+impl Shape {
+    pub fn overlaps(self, other: Shape) -> bool {
+        match (self, other) {
+            (Shape::Aabb { min: a, size: a_size }, Shape::Aabb { min: b, size: b_size }) => {
+                a.x < b.x + b_size.x && b.x < a.x + a_size.x && a.y < b.y + b_size.y && b.y < a.y + a_size.y
+            }
+            (Shape::Circle { center: a, radius: ra }, Shape::Circle { center: b, radius: rb }) => {
+                a.distance_squared(b) < (ra + rb) * (ra + rb)
+            }
+            (Shape::Aabb { min, size }, Shape::Circle { center, radius })
+            | (Shape::Circle { center, radius }, Shape::Aabb { min, size }) => {
+                // Najbliższy środkowi koła punkt prostokąta
+                let closest = center.clamp(min, min + size);
+                center.distance_squared(closest) < radius * radius
+            }
+        }
+    }
+    // Prostokąt otaczający: lewy górny i prawy dolny róg
+    pub fn bounds(self) -> (Vec2, Vec2) {
+        match self {
+            Shape::Aabb { min, size } => (min, min + size),
+            Shape::Circle { center, radius } => (center - Vec2::splat(radius), center + Vec2::splat(radius)),
+        }
+    }
+    // Środek kształtu (np. do celowania)
+    pub fn center(self) -> Vec2 {
+        match self {
+            Shape::Aabb { min, size } => min + size / 2.0,
+            Shape::Circle { center, .. } => center,
+        }
+    }
+}
+// Kształty obiektów gry według ich `pos`: prostokąty od lewego górnego rogu, koła od środka
+pub fn player(pos: Vec2) -> Shape {
+    Shape::Aabb { min: pos, size: PLAYER_SIZE }
+}
+// Rozmiary wrogów, pocisków i power-upów zależą od rodzaju (patrz `defs`)
+pub fn enemy(pos: Vec2, size: Vec2) -> Shape {
+    Shape::Aabb { min: pos, size }
+}
+pub fn bullet(pos: Vec2, radius: f32) -> Shape {
+    Shape::Circle { center: pos, radius }
+}
+pub fn power_up(pos: Vec2, radius: f32) -> Shape {
+    Shape::Circle { center: pos, radius }
+}
+// Punkt na środku górnej krawędzi gracza - stąd wylatują pociski
+pub fn muzzle(pos: Vec2) -> Vec2 {
+    pos + vec2(PLAYER_SIZE.x / 2.0, 0.0)
+}
+// Faza wstępna kolizji: jednolita siatka komórek, w których zapisane są obiekty sięgające
+// danej komórki. Zapytanie zwraca kandydatów do dokładnego testu `overlaps`
+pub struct SpatialHash<T> {
+    cell: f32,
+    cells: HashMap<(i32, i32), Vec<T>>,
+}
+impl<T: Copy + Ord> SpatialHash<T> {
+    pub fn new(cell: f32) -> SpatialHash<T> {
+        SpatialHash { cell, cells: HashMap::new() }
+    }
+    // Opróżnienie z zachowaniem zaalokowanych komórek
+    pub fn clear(&mut self) {
+        for items in self.cells.values_mut() {
+            items.clear();
+        }
+    }
+    fn cell_range(&self, shape: Shape) -> ((i32, i32), (i32, i32)) {
+        let (min, max) = shape.bounds();
+        let cell = |v: Vec2| ((v.x / self.cell).floor() as i32, (v.y / self.cell).floor() as i32);
+        (cell(min), cell(max))
+    }
+    pub fn insert(&mut self, item: T, shape: Shape) {
+        let ((x0, y0), (x1, y1)) = self.cell_range(shape);
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                self.cells.entry((x, y)).or_default().push(item);
+            }
+        }
+    }
+    // Kandydaci z komórek, których dotyka kształt - posortowani i bez powtórzeń,
+    // żeby wynik nie zależał od kolejności w tablicy mieszającej
+    pub fn query(&self, shape: Shape) -> Vec<T> {
+        let ((x0, y0), (x1, y1)) = self.cell_range(shape);
+        let mut found = vec![];
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                if let Some(items) = self.cells.get(&(x, y)) {
+                    found.extend_from_slice(items);
+                }
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boxes_touching_at_edges_do_not_collide() {
+        let a = enemy(vec2(0.0, 0.0), ENEMY_SIZE);
+        assert!(!a.overlaps(enemy(vec2(20.0, 0.0), ENEMY_SIZE)));
+        assert!(!a.overlaps(enemy(vec2(0.0, 20.0), ENEMY_SIZE)));
+        assert!(!a.overlaps(enemy(vec2(20.0, 20.0), ENEMY_SIZE)));
+        assert!(a.overlaps(enemy(vec2(19.9, 19.9), ENEMY_SIZE)));
+        assert!(a.overlaps(player(vec2(-19.9, 5.0))));
+    }
+
+    #[test]
+    fn circle_misses_box_corner_diagonally() {
+        let target = enemy(vec2(100.0, 100.0), ENEMY_SIZE);
+        // Po przekątnej od rogu: 4*sqrt(2) > 5, choć prostokąty otaczające się przecinają
+        assert!(!target.overlaps(bullet(vec2(124.0, 124.0), BULLET_RADIUS)));
+        assert!(target.overlaps(bullet(vec2(123.0, 123.0), BULLET_RADIUS)));
+        // Przy krawędzi liczy się tylko odległość w jednej osi
+        assert!(target.overlaps(bullet(vec2(110.0, 95.1), BULLET_RADIUS)));
+        assert!(!target.overlaps(bullet(vec2(110.0, 95.0), BULLET_RADIUS)));
+        // Koło w całości wewnątrz prostokąta
+        assert!(bullet(vec2(110.0, 110.0), BULLET_RADIUS).overlaps(target));
+    }
+
+    #[test]
+    fn circles_collide_by_distance() {
+        assert!(bullet(vec2(0.0, 0.0), BULLET_RADIUS).overlaps(power_up(vec2(14.9, 0.0), POWER_UP_RADIUS)));
+        assert!(!bullet(vec2(0.0, 0.0), BULLET_RADIUS).overlaps(power_up(vec2(15.0, 0.0), POWER_UP_RADIUS)));
+        assert!(!bullet(vec2(0.0, 0.0), BULLET_RADIUS).overlaps(power_up(vec2(11.0, 11.0), POWER_UP_RADIUS)));
+    }
+
+    #[test]
+    fn centers_match_drawn_shapes() {
+        assert_eq!(enemy(vec2(10.0, 10.0), ENEMY_SIZE).center(), vec2(20.0, 20.0));
+        assert_eq!(bullet(vec2(10.0, 10.0), BULLET_RADIUS).center(), vec2(10.0, 10.0));
+        assert_eq!(muzzle(vec2(10.0, 10.0)), vec2(20.0, 10.0));
+    }
+
+    #[test]
+    fn spatial_hash_finds_every_overlap() {
+        use crate::rng::GameRng;
+        use ::rand::Rng;
+        let mut rng = GameRng::new(3);
+        let enemies: Vec<Shape> = (0..300).map(|_| enemy(vec2(rng.gen_range(-50.0..500.0), rng.gen_range(-50.0..650.0)), ENEMY_SIZE)).collect();
+        let mut grid = SpatialHash::new(40.0);
+        for (i, &shape) in enemies.iter().enumerate() {
+            grid.insert(i, shape);
+        }
+        for _ in 0..300 {
+            let probe = bullet(vec2(rng.gen_range(-50.0..500.0), rng.gen_range(-50.0..650.0)), BULLET_RADIUS);
+            let candidates = grid.query(probe);
+            let brute: Vec<usize> = (0..enemies.len()).filter(|&i| probe.overlaps(enemies[i])).collect();
+            let found: Vec<usize> = candidates.iter().copied().filter(|&i| probe.overlaps(enemies[i])).collect();
+            assert_eq!(found, brute);
+            // Siatka odrzuca większość obiektów bez dokładnego testu
+            assert!(candidates.len() < 30);
+        }
+        grid.clear();
+        assert!(grid.query(enemy(vec2(100.0, 100.0), ENEMY_SIZE)).is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+use macroquad::prelude::{is_key_down, is_key_pressed, KeyCode};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use crate::world::Input;
+// Akcje gracza niezależne od klawiszy
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Fire,
+    Pause,
+    Bomb, // zarezerwowane - bomba nie ma jeszcze efektu w grze
+}
+impl Action {
+    pub const ALL: [Action; 7] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::Fire,
+        Action::Pause,
+        Action::Bomb,
+    ];
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::Fire => "Fire",
+            Action::Pause => "Pause",
+            Action::Bomb => "Bomb",
+        }
+    }
+    // Domyślnie strzałki + WASD jako drugi zestaw
+    fn default_keys(self) -> [Option<Key>; 2] {
+        let (primary, alternate) = match self {
+            Action::MoveLeft => (KeyCode::Left, Some(KeyCode::A)),
+            Action::MoveRight => (KeyCode::Right, Some(KeyCode::D)),
+            Action::MoveUp => (KeyCode::Up, Some(KeyCode::W)),
+            Action::MoveDown => (KeyCode::Down, Some(KeyCode::S)),
+            Action::Fire => (KeyCode::Space, Some(KeyCode::J)),
+            Action::Pause => (KeyCode::Escape, Some(KeyCode::P)),
+            Action::Bomb => (KeyCode::B, Some(KeyCode::K)),
+        };
+        [Some(Key(primary)), alternate.map(Key)]
+    }
+}
+// Klawisze, które można przypisać (zapisywane w ustawieniach pod nazwą)
+const BINDABLE: [KeyCode; 58] = [
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G, KeyCode::H, KeyCode::I,
+    KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N, KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R,
+    KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Left, KeyCode::Right, KeyCode::Up, KeyCode::Down,
+    KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab,
+    KeyCode::LeftShift, KeyCode::RightShift, KeyCode::LeftControl, KeyCode::RightControl,
+    KeyCode::LeftAlt, KeyCode::RightAlt,
+    KeyCode::Comma, KeyCode::Period, KeyCode::Slash, KeyCode::Semicolon,
+    KeyCode::Apostrophe, KeyCode::LeftBracket, KeyCode::RightBracket, KeyCode::Minus,
+];
+// Klawisz zapisywany w JSON jako nazwa, np. "Left" albo "A"
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Key(pub KeyCode);
+impl Key {
+    pub fn name(self) -> String {
+        format!("{:?}", self.0)
+    }
+    pub fn from_name(name: &str) -> Option<Key> {
+        BINDABLE.iter().copied().map(Key).find(|key| key.name() == name)
+    }
+    // Klawisz wciśnięty w tej klatce, o ile można go przypisać
+    pub fn just_pressed() -> Option<Key> {
+        BINDABLE.iter().copied().find(|&code| is_key_pressed(code)).map(Key)
+    }
+}
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Key, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Key::from_name(&name).ok_or_else(|| serde::de::Error::custom(format!("unknown key: {}", name)))
+    }
+}
+// Przypisania: dla każdej akcji klawisz główny i zapasowy
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct Bindings {
+    // Brak akcji w zapisanych ustawieniach = domyślne klawisze
+    keys: BTreeMap<Action, [Option<Key>; 2]>,
+}
+impl Bindings {
+    pub fn keys(&self, action: Action) -> [Option<Key>; 2] {
+        self.keys.get(&action).copied().unwrap_or_else(|| action.default_keys())
+    }
+    // Akcja, która już używa klawisza (poza wskazanym miejscem)
+    pub fn conflict(&self, action: Action, slot: usize, key: Key) -> Option<Action> {
+        Action::ALL.into_iter().find(|&other| {
+            self.keys(other)
+                .iter()
+                .enumerate()
+                .any(|(i, k)| *k == Some(key) && (other, i) != (action, slot))
+        })
+    }
+    // Przypisanie klawisza; przy konflikcie zwraca akcję, która go już używa
+    pub fn bind(&mut self, action: Action, slot: usize, key: Option<Key>) -> Result<(), Action> {
+        if let Some(other) = key.and_then(|key| self.conflict(action, slot, key)) {
+            return Err(other);
+        }
+        let mut keys = self.keys(action);
+        keys[slot] = key;
+        self.keys.insert(action, keys);
+        Ok(())
+    }
+    pub fn down(&self, action: Action) -> bool {
+        self.keys(action).iter().flatten().any(|key| is_key_down(key.0))
+    }
+    pub fn pressed(&self, action: Action) -> bool {
+        self.keys(action).iter().flatten().any(|key| is_key_pressed(key.0))
+    }
+    // Stan wejścia dla symulacji
+    pub fn read_input(&self) -> Input {
+        Input {
+            left: self.down(Action::MoveLeft),
+            right: self.down(Action::MoveRight),
+            up: self.down(Action::MoveUp),
+            down: self.down(Action::MoveDown),
+            fire: self.down(Action::Fire),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_include_wasd() {
+        let bindings = Bindings::default();
+        assert_eq!(bindings.keys(Action::MoveUp), [Some(Key(KeyCode::Up)), Some(Key(KeyCode::W))]);
+        assert_eq!(bindings.conflict(Action::Fire, 0, Key(KeyCode::A)), Some(Action::MoveLeft));
+        assert_eq!(bindings.conflict(Action::MoveLeft, 1, Key(KeyCode::A)), None);
+    }
+
+    #[test]
+    fn rebinding_detects_conflicts() {
+        let mut bindings = Bindings::default();
+        assert_eq!(bindings.bind(Action::Fire, 0, Some(Key(KeyCode::W))), Err(Action::MoveUp));
+        assert_eq!(bindings.keys(Action::Fire)[0], Some(Key(KeyCode::Space)));
+        // Zwolniony klawisz można przypisać innej akcji
+        bindings.bind(Action::MoveUp, 1, None).unwrap();
+        bindings.bind(Action::Fire, 0, Some(Key(KeyCode::W))).unwrap();
+        assert_eq!(bindings.keys(Action::Fire), [Some(Key(KeyCode::W)), Some(Key(KeyCode::J))]);
+    }
+
+    #[test]
+    fn bindings_are_saved_by_key_name() {
+        let mut bindings = Bindings::default();
+        bindings.bind(Action::Bomb, 0, Some(Key(KeyCode::LeftShift))).unwrap();
+        let json = serde_json::to_string(&bindings).unwrap();
+        assert_eq!(json, r#"{"keys":{"Bomb":["LeftShift","K"]}}"#);
+        assert_eq!(serde_json::from_str::<Bindings>(&json).unwrap(), bindings);
+        assert!(serde_json::from_str::<Bindings>(r#"{"keys":{"Fire":["NoSuchKey",null]}}"#).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+// Sekundy od 1970-01-01 (UTC)
+pub fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+// Data (rok, miesiąc, dzień) w UTC z liczby dni od 1970-01-01
+pub fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+    (year, month, day)
+}
+// Dzisiejsza data w UTC
+pub fn today() -> (i32, u32, u32) {
+    civil_from_days((now_secs() / 86_400) as i64)
+}
+// Znacznik czasu w postaci "RRRR-MM-DD GG:MM" (UTC)
+pub fn format_timestamp(secs: u64) -> String {
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let minutes = secs % 86_400 / 60;
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, minutes / 60, minutes % 60)
+}
+// Czas gry w postaci "M:SS" albo "G:MM:SS"
+pub fn format_duration(secs: f64) -> String {
+    let total = secs.max(0.0) as u64;
+    if total >= 3600 {
+        format!("{}:{:02}:{:02}", total / 3600, total % 3600 / 60, total % 60)
+    } else {
+        format!("{}:{:02}", total / 60, total % 60)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_743), (2026, 10, 17));
+        assert_eq!(format_timestamp(20_743 * 86_400 + 13 * 3600 + 5 * 60), "2026-10-17 13:05");
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(65.9), "1:05");
+        assert_eq!(format_duration(3725.0), "1:02:05");
+    }
+}
@@ -0,0 +1,454 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::sync::OnceLock;
+use crate::canvas::PLAYFIELD;
+use crate::collision::{BULLET_RADIUS, ENEMY_SIZE, POWER_UP_RADIUS};
+use crate::kinds::{BulletKind, EnemyKind, PowerUpKind};
+use crate::world::DEFAULT_PIERCE;
+// Plik z definicjami wrogów, pocisków i power-upów; bez niego gra używa wbudowanego zestawu
+pub const DEFINITIONS_FILE: &str = "definitions.json";
+// Górna granica wagi pojawiania się (suma wag musi się zmieścić w u32)
+const MAX_SPAWN_WEIGHT: u32 = 10_000;
+// Górne granice zdrowia, poziomu strzału i salwy wroga - większe wartości zalewałyby planszę pociskami
+const MAX_HEALTH: i32 = 99;
+const MAX_SHOT_LEVEL: u32 = 10;
+const MAX_VOLLEY: u32 = 16;
+// Górna granica prędkości (jednostki planszy na sekundę) - szybsze obiekty mijałyby się w jednym kroku
+const MAX_SPEED: f32 = 1_000.0;
+// Rodzaje obiektów gry - dane zamiast kodu, żeby nowego wroga dało się dodać bez zmian w grze
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Definitions {
+    pub player_bullet: KindRef<BulletKind>, // pocisk gracza
+    pub power_bullet: KindRef<BulletKind>,  // pocisk gracza przy wzmocnionym strzale (shot_level > 1)
+    pub enemies: Vec<EnemyDef>,
+    pub bullets: Vec<BulletDef>,
+    pub power_ups: Vec<PowerUpDef>,
+}
+// Rodzaj wroga
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct EnemyDef {
+    pub name: String,
+    pub spawn_weight: u32, // szansa pojawienia się względem innych wrogów; 0 - nie pojawia się sam
+    pub speed: f32, // przy trudności 1 na pierwszym levelu
+    pub health: i32,
+    pub score: i32,
+    #[serde(with = "color")]
+    pub color: Color,
+    pub width: f32,
+    pub height: f32,
+    #[serde(default)]
+    pub movement: Movement,
+    #[serde(default)]
+    pub firing: Firing,
+    #[serde(default)]
+    pub drops: Vec<Drop>,
+    pub explosion: Explosion,
+}
+impl EnemyDef {
+    pub fn size(&self) -> Vec2 {
+        vec2(self.width, self.height)
+    }
+}
+// Ruch wroga: zawsze w dół, opcjonalnie z kołysaniem na boki
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(tag = "type")]
+pub enum Movement {
+    #[default]
+    Straight,
+    Weave { amplitude: f32, period: f32 }, // wychylenie w jednostkach planszy, okres w sekundach
+}
+// Strzelanie wroga
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(tag = "type")]
+pub enum Firing {
+    #[default]
+    None,
+    // Pociski w stronę gracza; przy `count` > 1 rozłożone co `spread` stopni
+    Aimed {
+        interval: f64, // sekundy między strzałami na pierwszym levelu
+        bullet: KindRef<BulletKind>,
+        speed: f32,
+        #[serde(default = "one")]
+        count: u32,
+        #[serde(default)]
+        spread: f32,
+    },
+}
+fn one() -> u32 {
+    1
+}
+// Power-up, który może wypaść z zestrzelonego wroga
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Drop {
+    pub power_up: KindRef<PowerUpKind>,
+    pub chance: f32, // 0-1; pierwszy wylosowany z listy wypada, reszta nie
+}
+// Odwołanie do rodzaju po nazwie (w pliku jest tylko nazwa). Indeks ustala `Definitions::resolve`,
+// żeby gra nie szukała nazw przy każdym strzale
+#[derive(Clone, PartialEq, Debug)]
+pub struct KindRef<K> {
+    pub name: String,
+    kind: Option<K>,
+}
+impl<K: Copy> KindRef<K> {
+    pub fn new(name: &str) -> KindRef<K> {
+        KindRef { name: name.to_string(), kind: None }
+    }
+    pub fn kind(&self) -> K {
+        self.kind.expect("definitions are resolved when built or loaded")
+    }
+}
+impl<K> Serialize for KindRef<K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.name.serialize(serializer)
+    }
+}
+impl<'de, K> Deserialize<'de> for KindRef<K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<KindRef<K>, D::Error> {
+        Ok(KindRef { name: String::deserialize(deserializer)?, kind: None })
+    }
+}
+// Dźwięk eksplozji wroga
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Explosion {
+    Small,
+    Sharp,
+    Heavy,
+    Medium,
+}
+// Rodzaj pocisku
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct BulletDef {
+    pub name: String,
+    pub damage: i32,
+    #[serde(with = "color")]
+    pub color: Color,
+    pub radius: f32,
+    #[serde(default = "default_pierce")]
+    pub pierce: u32, // ilu wrogów trafia pocisk gracza
+}
+fn default_pierce() -> u32 {
+    DEFAULT_PIERCE
+}
+// Rodzaj power-upu
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PowerUpDef {
+    pub name: String,
+    pub spawn_weight: u32, // 0 - tylko z wrogów
+    pub speed: f32,
+    #[serde(with = "color")]
+    pub color: Color,
+    pub radius: f32,
+    pub effect: Effect,
+}
+// Działanie power-upu po zebraniu
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type")]
+pub enum Effect {
+    Heal { amount: i32, max: i32 },
+    ExtraShot { amount: u32, max: u32 },
+}
+// Kolor zapisany jako [r, g, b, a] w zakresie 0-1
+mod color {
+    use macroquad::prelude::Color;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        [color.r, color.g, color.b, color.a].serialize(serializer)
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let [r, g, b, a] = <[f32; 4]>::deserialize(deserializer)?;
+        Ok(Color::new(r, g, b, a))
+    }
+}
+// Wbudowany zestaw: klasyczni wrogowie normal/fast/tank/shooter
+impl Default for Definitions {
+    fn default() -> Definitions {
+        let enemy = |name: &str, speed, health, score, color, explosion| EnemyDef {
+            name: name.to_string(),
+            spawn_weight: 1,
+            speed,
+            health,
+            score,
+            color,
+            width: ENEMY_SIZE.x,
+            height: ENEMY_SIZE.y,
+            movement: Movement::Straight,
+            firing: Firing::None,
+            drops: vec![],
+            explosion,
+        };
+        let bullet = |name: &str, damage, color| BulletDef { name: name.to_string(), damage, color, radius: BULLET_RADIUS, pierce: DEFAULT_PIERCE };
+        let power_up = |name: &str, color, effect| PowerUpDef { name: name.to_string(), spawn_weight: 1, speed: 80.0, color, radius: POWER_UP_RADIUS, effect };
+        let shooter = EnemyDef {
+            firing: Firing::Aimed { interval: 1.5, bullet: KindRef::new("Enemy"), speed: 200.0, count: 1, spread: 0.0 },
+            ..enemy("Shooter", 80.0, 2, 40, ORANGE, Explosion::Medium)
+        };
+        let mut defs = Definitions {
+            player_bullet: KindRef::new("Normal"),
+            power_bullet: KindRef::new("Power"),
+            enemies: vec![
+                enemy("Normal", 100.0, 1, 10, RED, Explosion::Small),
+                enemy("Fast", 150.0, 1, 20, BLUE, Explosion::Sharp),
+                enemy("Tank", 100.0, 3, 30, PURPLE, Explosion::Heavy),
+                shooter,
+            ],
+            bullets: vec![bullet("Normal", 1, YELLOW), bullet("Power", 2, ORANGE), bullet("Enemy", 1, RED)],
+            power_ups: vec![
+                power_up("Health", GREEN, Effect::Heal { amount: 1, max: 5 }),
+                power_up("MultiShot", GOLD, Effect::ExtraShot { amount: 1, max: 5 }),
+            ],
+        };
+        defs.resolve();
+        defs
+    }
+}
+impl Definitions {
+    pub fn enemy(&self, kind: EnemyKind) -> &EnemyDef {
+        &self.enemies[kind.0]
+    }
+    pub fn bullet(&self, kind: BulletKind) -> &BulletDef {
+        &self.bullets[kind.0]
+    }
+    pub fn power_up(&self, kind: PowerUpKind) -> &PowerUpDef {
+        &self.power_ups[kind.0]
+    }
+    // Indeksy rodzajów we wszystkich odwołaniach; tylko dla definicji, które przeszły `validate`
+    pub fn resolve(&mut self) {
+        let bullets: Vec<String> = self.bullets.iter().map(|def| def.name.clone()).collect();
+        let power_ups: Vec<String> = self.power_ups.iter().map(|def| def.name.clone()).collect();
+        let index = |names: &[String], name: &str| names.iter().position(|n| n == name).expect("validated definitions refer only to known kinds");
+        for bullet in [&mut self.player_bullet, &mut self.power_bullet] {
+            bullet.kind = Some(BulletKind(index(&bullets, &bullet.name)));
+        }
+        for enemy in &mut self.enemies {
+            if let Firing::Aimed { bullet, .. } = &mut enemy.firing {
+                bullet.kind = Some(BulletKind(index(&bullets, &bullet.name)));
+            }
+            for drop in &mut enemy.drops {
+                drop.power_up.kind = Some(PowerUpKind(index(&power_ups, &drop.power_up.name)));
+            }
+        }
+    }
+    // Skrót definicji (FNV-1a z postaci JSON) - nagrania i wyzwanie dnia są ważne tylko przy tych samych definicjach
+    pub fn fingerprint(&self) -> u64 {
+        let data = serde_json::to_string(self).expect("definitions serialize to JSON");
+        data.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+    }
+    // Błędy w definicjach, każdy z miejscem, którego dotyczy (pusta lista - wszystko w porządku)
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = vec![];
+        let mut check = |ok: bool, at: &str, message: &str| {
+            if !ok {
+                errors.push(format!("{}: {}", at, message));
+            }
+        };
+        let bullets: Vec<&str> = self.bullets.iter().map(|def| def.name.as_str()).collect();
+        let power_ups: Vec<&str> = self.power_ups.iter().map(|def| def.name.as_str()).collect();
+        check(bullets.contains(&self.player_bullet.name.as_str()), "player_bullet", &format!("unknown bullet \"{}\"", self.player_bullet.name));
+        check(bullets.contains(&self.power_bullet.name.as_str()), "power_bullet", &format!("unknown bullet \"{}\"", self.power_bullet.name));
+        check(!self.enemies.is_empty(), "enemies", "at least one enemy is required");
+        check(self.enemies.iter().any(|def| def.spawn_weight > 0), "enemies", "at least one enemy needs spawn_weight above 0");
+        for (list, weights) in [("enemies", self.enemies.iter().map(|def| def.spawn_weight).collect::<Vec<_>>()), ("power_ups", self.power_ups.iter().map(|def| def.spawn_weight).collect())] {
+            check(weights.iter().all(|&weight| weight <= MAX_SPAWN_WEIGHT), list, &format!("spawn_weight must be at most {}", MAX_SPAWN_WEIGHT));
+        }
+        for (list, names) in [
+            ("enemies", self.enemies.iter().map(|def| def.name.as_str()).collect::<Vec<_>>()),
+            ("bullets", bullets.clone()),
+            ("power_ups", power_ups.clone()),
+        ] {
+            let mut seen = HashSet::new();
+            for (i, name) in names.into_iter().enumerate() {
+                let at = format!("{}[{}]", list, i);
+                check(!name.is_empty(), &at, "name is empty");
+                check(seen.insert(name), &at, &format!("duplicate name \"{}\"", name));
+            }
+        }
+        for (i, def) in self.enemies.iter().enumerate() {
+            let at = format!("enemies[{}] \"{}\"", i, def.name);
+            check(def.speed > 0.0, &at, "speed must be above 0");
+            check(def.speed <= MAX_SPEED, &at, &format!("speed must be at most {}", MAX_SPEED));
+            check(def.health > 0, &at, "health must be above 0");
+            check(def.score >= 0, &at, "score must be at least 0");
+            check(def.width > 0.0 && def.height > 0.0, &at, "width and height must be above 0");
+            check(def.width < PLAYFIELD.x && def.height < PLAYFIELD.y, &at, &format!("width and height must be below {}x{}", PLAYFIELD.x, PLAYFIELD.y));
+            if let Movement::Weave { amplitude, period } = def.movement {
+                check(amplitude >= 0.0 && period > 0.0, &at, "weave needs amplitude of at least 0 and period above 0");
+            }
+            if let Firing::Aimed { interval, bullet, speed, count, spread } = &def.firing {
+                check(*interval > 0.0, &at, "firing interval must be above 0");
+                check(*speed > 0.0, &at, "firing speed must be above 0");
+                check(*speed <= MAX_SPEED, &at, &format!("firing speed must be at most {}", MAX_SPEED));
+                check(*count > 0 && *spread >= 0.0, &at, "firing count must be above 0 and spread at least 0");
+                check(*count <= MAX_VOLLEY, &at, &format!("firing count must be at most {}", MAX_VOLLEY));
+                check(bullets.contains(&bullet.name.as_str()), &at, &format!("firing uses unknown bullet \"{}\"", bullet.name));
+            }
+            for drop in &def.drops {
+                check(power_ups.contains(&drop.power_up.name.as_str()), &at, &format!("drops unknown power-up \"{}\"", drop.power_up.name));
+                check((0.0..=1.0).contains(&drop.chance), &at, "drop chance must be between 0 and 1");
+            }
+        }
+        for (i, def) in self.bullets.iter().enumerate() {
+            let at = format!("bullets[{}] \"{}\"", i, def.name);
+            check(def.radius > 0.0, &at, "radius must be above 0");
+            check(def.pierce > 0, &at, "pierce must be above 0");
+            check(def.damage > 0, &at, "damage must be above 0");
+        }
+        for (i, def) in self.power_ups.iter().enumerate() {
+            let at = format!("power_ups[{}] \"{}\"", i, def.name);
+            check(def.speed > 0.0, &at, "speed must be above 0");
+            check(def.speed <= MAX_SPEED, &at, &format!("speed must be at most {}", MAX_SPEED));
+            check(def.radius > 0.0, &at, "radius must be above 0");
+            check(def.radius < PLAYFIELD.x / 2.0, &at, &format!("radius must be below {}", PLAYFIELD.x / 2.0));
+            match def.effect {
+                Effect::Heal { amount, max } => {
+                    check((1..=MAX_HEALTH).contains(&max), &at, &format!("heal max must be between 1 and {}", MAX_HEALTH));
+                    check((1..=max).contains(&amount), &at, "heal amount must be between 1 and max");
+                }
+                Effect::ExtraShot { amount, max } => {
+                    check((1..=MAX_SHOT_LEVEL).contains(&max), &at, &format!("extra shot max must be between 1 and {}", MAX_SHOT_LEVEL));
+                    check((1..=max).contains(&amount), &at, "extra shot amount must be between 1 and max");
+                }
+            }
+        }
+        errors
+    }
+}
+// Powód, dla którego definicji z pliku nie da się użyć
+#[derive(Debug)]
+pub enum DefsError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    Invalid(Vec<String>),
+}
+impl fmt::Display for DefsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DefsError::Io(e) => write!(f, "Could not read {}: {}", DEFINITIONS_FILE, e),
+            DefsError::Parse(e) => write!(f, "{} is not valid: {}", DEFINITIONS_FILE, e),
+            DefsError::Invalid(errors) => {
+                write!(f, "{} has {} error(s):", DEFINITIONS_FILE, errors.len())?;
+                for error in errors {
+                    write!(f, "\n  {}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+pub fn parse_definitions(data: &str) -> Result<Definitions, DefsError> {
+    let mut defs: Definitions = serde_json::from_str(data).map_err(DefsError::Parse)?;
+    let errors = defs.validate();
+    if errors.is_empty() {
+        defs.resolve();
+        Ok(defs)
+    } else {
+        Err(DefsError::Invalid(errors))
+    }
+}
+// Wczytanie definicji z pliku; brak pliku to wbudowany zestaw, błędny plik - wbudowany zestaw i błąd do pokazania
+pub fn load_definitions() -> (Definitions, Option<DefsError>) {
+    match fs::read_to_string(DEFINITIONS_FILE) {
+        Ok(data) => match parse_definitions(&data) {
+            Ok(defs) => (defs, None),
+            Err(e) => (Definitions::default(), Some(e)),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => (Definitions::default(), None),
+        Err(e) => (Definitions::default(), Some(DefsError::Io(e))),
+    }
+}
+static DEFINITIONS: OnceLock<Definitions> = OnceLock::new();
+// Definicje używane przez grę - ustawiane raz przy starcie, wcześniej (i w testach) wbudowane
+pub fn current() -> &'static Definitions {
+    DEFINITIONS.get_or_init(Definitions::default)
+}
+pub fn install(defs: Definitions) {
+    DEFINITIONS.set(defs).unwrap_or(());
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_file_matches_built_in_set() {
+        let defs = parse_definitions(include_str!("../definitions.json")).unwrap();
+        assert_eq!(defs, Definitions::default());
+        assert!(Definitions::default().validate().is_empty());
+    }
+
+    #[test]
+    fn fingerprint_follows_the_definitions() {
+        let defs = parse_definitions(include_str!("../definitions.json")).unwrap();
+        assert_eq!(defs.fingerprint(), Definitions::default().fingerprint());
+        let mut changed = Definitions::default();
+        changed.enemies[0].score += 1;
+        assert_ne!(changed.fingerprint(), defs.fingerprint());
+    }
+
+    #[test]
+    fn validation_reports_each_problem() {
+        let mut defs = Definitions::default();
+        defs.enemies[1].health = 0;
+        defs.enemies[3].firing = Firing::Aimed { interval: 1.0, bullet: KindRef::new("Laser"), speed: 100.0, count: 1, spread: 0.0 };
+        defs.enemies[2].drops.push(Drop { power_up: KindRef::new("Health"), chance: 1.5 });
+        defs.bullets[2].name = "Normal".to_string();
+        let errors = defs.validate();
+        assert_eq!(
+            errors,
+            vec![
+                "bullets[2]: duplicate name \"Normal\"",
+                "enemies[1] \"Fast\": health must be above 0",
+                "enemies[2] \"Tank\": drop chance must be between 0 and 1",
+                "enemies[3] \"Shooter\": firing uses unknown bullet \"Laser\"",
+            ]
+        );
+        let message = DefsError::Invalid(errors).to_string();
+        assert!(message.starts_with("definitions.json has 4 error(s):\n  bullets[2]"));
+    }
+
+    #[test]
+    fn validation_keeps_values_playable() {
+        let mut defs = Definitions::default();
+        defs.enemies[0].width = PLAYFIELD.x;
+        defs.enemies[1].score = -10;
+        defs.enemies[2].speed = 5_000.0;
+        defs.bullets[1].damage = 0;
+        defs.power_ups[0].speed = 1_500.0;
+        defs.enemies[3].firing = Firing::Aimed { interval: 1.0, bullet: KindRef::new("Enemy"), speed: 2_000.0, count: 1_000, spread: 1.0 };
+        defs.power_ups[0].effect = Effect::Heal { amount: i32::MAX, max: i32::MAX };
+        defs.power_ups[1].radius = 300.0;
+        defs.power_ups[1].effect = Effect::ExtraShot { amount: 1, max: 1_000_000 };
+        assert_eq!(
+            defs.validate(),
+            vec![
+                "enemies[0] \"Normal\": width and height must be below 480x640",
+                "enemies[1] \"Fast\": score must be at least 0",
+                "enemies[2] \"Tank\": speed must be at most 1000",
+                "enemies[3] \"Shooter\": firing speed must be at most 1000",
+                "enemies[3] \"Shooter\": firing count must be at most 16",
+                "bullets[1] \"Power\": damage must be above 0",
+                "power_ups[0] \"Health\": speed must be at most 1000",
+                "power_ups[0] \"Health\": heal max must be between 1 and 99",
+                "power_ups[1] \"MultiShot\": radius must be below 240",
+                "power_ups[1] \"MultiShot\": extra shot max must be between 1 and 10",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_problem() {
+        let error = parse_definitions("{\"player_bullet\": \"Normal\",\n \"enemies\": 3}").unwrap_err();
+        assert!(matches!(error, DefsError::Parse(_)));
+        assert!(error.to_string().contains("line 2"), "{}", error);
+        // Pola z wartością domyślną można pominąć
+        let data = r#"{"player_bullet":"A","power_bullet":"A",
+            "enemies":[{"name":"Drone","spawn_weight":1,"speed":50,"health":1,"score":5,"color":[1,1,1,1],"width":10,"height":10,"explosion":"Small"}],
+            "bullets":[{"name":"A","damage":1,"color":[1,1,0,1],"radius":3}],"power_ups":[]}"#;
+        let defs = parse_definitions(data).unwrap();
+        assert_eq!(defs.enemies[0].movement, Movement::Straight);
+        assert_eq!(defs.bullets[0].pierce, DEFAULT_PIERCE);
+    }
+}
@@ -0,0 +1,154 @@
+use macroquad::prelude::{get_time, request_new_screen_size, set_fullscreen, Conf};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use crate::canvas::PLAYFIELD;
+// Rozmiary okna do wyboru w ustawieniach (wielokrotności planszy 480x640)
+pub const WINDOW_SIZES: [(u32, u32); 4] = [(480, 640), (600, 800), (720, 960), (960, 1280)];
+// Limity klatek do wyboru; None = bez limitu
+pub const FPS_CAPS: [Option<u32>; 5] = [None, Some(30), Some(60), Some(120), Some(144)];
+// Dopuszczalne wartości - spoza nich ustawienia wracają do domyślnych
+const MIN_SIZE: (u32, u32) = (240, 320);
+const MAX_SIZE: (u32, u32) = (7680, 4320);
+const MIN_FPS: u32 = 15;
+const MAX_FPS: u32 = 1000;
+// Ustawienia okna
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+    pub vsync: bool, // zmiana działa dopiero po ponownym uruchomieniu
+    pub fps_cap: Option<u32>,
+}
+impl Default for DisplaySettings {
+    fn default() -> DisplaySettings {
+        DisplaySettings {
+            width: PLAYFIELD.x as u32,
+            height: PLAYFIELD.y as u32,
+            fullscreen: false,
+            vsync: true,
+            fps_cap: None,
+        }
+    }
+}
+impl DisplaySettings {
+    // Wartości poprawione do bezpiecznych: zły rozmiar okna wraca do domyślnego, zły limit FPS jest wyłączany
+    pub fn sanitized(self) -> DisplaySettings {
+        let mut display = self;
+        let valid_size = (MIN_SIZE.0..=MAX_SIZE.0).contains(&self.width) && (MIN_SIZE.1..=MAX_SIZE.1).contains(&self.height);
+        if !valid_size {
+            let default = DisplaySettings::default();
+            display.width = default.width;
+            display.height = default.height;
+        }
+        display.fps_cap = self.fps_cap.filter(|fps| (MIN_FPS..=MAX_FPS).contains(fps));
+        display
+    }
+    // Konfiguracja okna przy starcie gry
+    pub fn conf(&self) -> Conf {
+        let mut conf = Conf {
+            window_title: "StarBlaster".to_string(),
+            window_width: self.width as i32,
+            window_height: self.height as i32,
+            fullscreen: self.fullscreen,
+            window_resizable: true,
+            ..Default::default()
+        };
+        conf.platform.swap_interval = Some(self.vsync as i32);
+        conf
+    }
+    // Następny rozmiar z listy (spoza listy - pierwszy)
+    pub fn next_size(&mut self) {
+        let index = WINDOW_SIZES.iter().position(|&size| size == (self.width, self.height));
+        (self.width, self.height) = WINDOW_SIZES[index.map_or(0, |i| (i + 1) % WINDOW_SIZES.len())];
+    }
+    // Następny limit klatek z listy
+    pub fn next_fps_cap(&mut self) {
+        let index = FPS_CAPS.iter().position(|&cap| cap == self.fps_cap);
+        self.fps_cap = FPS_CAPS[index.map_or(0, |i| (i + 1) % FPS_CAPS.len())];
+    }
+}
+// Zmiany, które da się zastosować w trakcie gry: pełny ekran i rozmiar okna
+pub fn apply(previous: &DisplaySettings, current: &DisplaySettings) {
+    if previous.fullscreen != current.fullscreen {
+        set_fullscreen(current.fullscreen);
+    }
+    let resized = (previous.width, previous.height) != (current.width, current.height);
+    if !current.fullscreen && (resized || previous.fullscreen) {
+        request_new_screen_size(current.width as f32, current.height as f32);
+    }
+}
+// Uszkodzona sekcja okna w pliku ustawień nie kasuje reszty ustawień
+pub fn lenient<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DisplaySettings, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value::<DisplaySettings>(value).unwrap_or_default().sanitized())
+}
+// Ogranicznik klatek: czeka do końca klatki o długości wynikającej z limitu
+pub struct FrameLimiter {
+    frame_start: f64,
+}
+impl FrameLimiter {
+    pub fn new() -> FrameLimiter {
+        FrameLimiter { frame_start: get_time() }
+    }
+    // Wywoływane przed next_frame
+    pub fn wait(&mut self, cap: Option<u32>) {
+        if let Some(delay) = frame_delay(cap, get_time() - self.frame_start) {
+            std::thread::sleep(delay);
+        }
+        self.frame_start = get_time();
+    }
+}
+// Czas do odczekania po klatce trwającej `elapsed` sekund
+fn frame_delay(cap: Option<u32>, elapsed: f64) -> Option<Duration> {
+    let frame = 1.0 / cap? as f64;
+    (elapsed < frame).then(|| Duration::from_secs_f64(frame - elapsed))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save::Settings;
+
+    #[test]
+    fn invalid_values_fall_back() {
+        let display = DisplaySettings { width: 0, height: 100_000, fullscreen: true, vsync: false, fps_cap: Some(2) };
+        let fixed = display.sanitized();
+        assert_eq!((fixed.width, fixed.height, fixed.fps_cap), (480, 640, None));
+        assert!(fixed.fullscreen && !fixed.vsync);
+        let ok = DisplaySettings { width: 1920, height: 1080, fps_cap: Some(60), ..DisplaySettings::default() };
+        assert_eq!(ok.sanitized(), ok);
+    }
+
+    #[test]
+    fn broken_display_section_keeps_other_settings() {
+        let settings: Settings = serde_json::from_str(r#"{"difficulty":2.0,"master_volume":0.4,"display":{"width":"big"}}"#).unwrap();
+        assert_eq!(settings.difficulty, 2.0);
+        assert_eq!(settings.display, DisplaySettings::default());
+        let settings: Settings = serde_json::from_str(r#"{"difficulty":2.0,"master_volume":0.4,"display":{"vsync":false,"fps_cap":9999}}"#).unwrap();
+        assert!(!settings.display.vsync);
+        assert_eq!(settings.display.fps_cap, None);
+    }
+
+    #[test]
+    fn options_cycle() {
+        let mut display = DisplaySettings { width: 1000, height: 1000, ..DisplaySettings::default() };
+        display.next_size();
+        assert_eq!((display.width, display.height), WINDOW_SIZES[0]);
+        display.next_size();
+        assert_eq!((display.width, display.height), WINDOW_SIZES[1]);
+        for _ in 0..FPS_CAPS.len() {
+            display.next_fps_cap();
+        }
+        assert_eq!(display.fps_cap, None);
+    }
+
+    #[test]
+    fn limiter_waits_for_the_rest_of_the_frame() {
+        assert_eq!(frame_delay(None, 0.0), None);
+        assert_eq!(frame_delay(Some(50), 0.05), None);
+        let delay = frame_delay(Some(50), 0.015).unwrap();
+        assert!((delay.as_secs_f64() - 0.005).abs() < 1e-9);
+    }
+}
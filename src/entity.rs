@@ -0,0 +1,164 @@
+// Przechowywanie obiektów gry: sloty wielokrotnego użytku z numerem generacji.
+// Uchwyt (`Handle`) do usuniętego obiektu przestaje działać, nawet gdy slot dostał nowy obiekt
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+    despawning: bool, // usunięty w tym kroku, slot zostanie zwolniony w `flush`
+}
+pub struct Pool<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,      // wolne sloty do ponownego użycia
+    despawned: Vec<u32>, // sloty czekające na zwolnienie
+    live: usize,
+}
+impl<T> Pool<T> {
+    pub fn new() -> Pool<T> {
+        Pool { slots: vec![], free: vec![], despawned: vec![], live: 0 }
+    }
+    pub fn spawn(&mut self, value: T) -> Handle {
+        self.live += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.value = Some(value);
+            return Handle { index, generation: slot.generation };
+        }
+        self.slots.push(Slot { generation: 0, value: Some(value), despawning: false });
+        Handle { index: self.slots.len() as u32 - 1, generation: 0 }
+    }
+    fn slot(&self, handle: Handle) -> Option<&Slot<T>> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation && !slot.despawning)
+    }
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.slot(handle)?.value.as_ref()
+    }
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.slot(handle)?;
+        self.slots[handle.index as usize].value.as_mut()
+    }
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+    // Usunięcie odroczone: obiekt od razu znika z `get` i iteracji, ale slot (i uchwyty
+    // innych obiektów) pozostaje bez zmian do `flush`. Zwraca false dla nieaktualnego uchwytu
+    pub fn despawn(&mut self, handle: Handle) -> bool {
+        if !self.contains(handle) {
+            return false;
+        }
+        self.slots[handle.index as usize].despawning = true;
+        self.despawned.push(handle.index);
+        self.live -= 1;
+        true
+    }
+    // Usunięcie wszystkich obiektów spełniających warunek
+    pub fn despawn_where(&mut self, mut condition: impl FnMut(&T) -> bool) {
+        let doomed: Vec<Handle> = self.iter().filter(|(_, value)| condition(value)).map(|(handle, _)| handle).collect();
+        for handle in doomed {
+            self.despawn(handle);
+        }
+    }
+    // Zwolnienie slotów usuniętych obiektów; nowa generacja unieważnia stare uchwyty
+    pub fn flush(&mut self) {
+        for index in self.despawned.drain(..) {
+            let slot = &mut self.slots[index as usize];
+            slot.value = None;
+            slot.despawning = false;
+            slot.generation = slot.generation.wrapping_add(1);
+            self.free.push(index);
+        }
+    }
+    // Liczba żywych obiektów (na razie potrzebna tylko w testach)
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.live
+    }
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+    // Żywe obiekty w kolejności slotów
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.slots.iter().enumerate().filter(|(_, slot)| !slot.despawning).filter_map(|(index, slot)| {
+            let handle = Handle { index: index as u32, generation: slot.generation };
+            slot.value.as_ref().map(|value| (handle, value))
+        })
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle, &mut T)> {
+        self.slots.iter_mut().enumerate().filter(|(_, slot)| !slot.despawning).filter_map(|(index, slot)| {
+            let handle = Handle { index: index as u32, generation: slot.generation };
+            slot.value.as_mut().map(|value| (handle, value))
+        })
+    }
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.iter().map(|(_, value)| value)
+    }
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.iter_mut().map(|(_, value)| value)
+    }
+}
+impl<T> FromIterator<T> for Pool<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Pool<T> {
+        let mut pool = Pool::new();
+        for value in iter {
+            pool.spawn(value);
+        }
+        pool
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn despawn_is_deferred_until_flush() {
+        let mut pool: Pool<&str> = ["a", "b", "c"].into_iter().collect();
+        let handles: Vec<Handle> = pool.iter().map(|(h, _)| h).collect();
+        assert!(pool.despawn(handles[1]));
+        // Drugi raz ten sam obiekt nie jest usuwany
+        assert!(!pool.despawn(handles[1]));
+        assert_eq!(pool.get(handles[1]), None);
+        assert_eq!(pool.values().copied().collect::<Vec<_>>(), vec!["a", "c"]);
+        assert_eq!(pool.len(), 2);
+        // Przed flush slot nie jest jeszcze wolny
+        let d = pool.spawn("d");
+        assert_ne!(d.index, handles[1].index);
+        pool.flush();
+        assert_eq!(pool.get(handles[2]), Some(&"c"));
+    }
+
+    #[test]
+    fn slots_are_reused_with_new_generation() {
+        let mut pool = Pool::new();
+        let first = pool.spawn(1);
+        pool.despawn(first);
+        pool.flush();
+        let second = pool.spawn(2);
+        assert_eq!(pool.slots.len(), 1);
+        assert_eq!(second.index, first.index);
+        // Stary uchwyt nie wskazuje nowego obiektu
+        assert_eq!(pool.get(first), None);
+        assert!(!pool.despawn(first));
+        assert_eq!(pool.get(second), Some(&2));
+        *pool.get_mut(second).unwrap() += 1;
+        assert_eq!(pool.values().copied().collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn despawn_where_removes_matching() {
+        let mut pool: Pool<i32> = (0..6).collect();
+        pool.despawn_where(|v| v % 2 == 0);
+        pool.flush();
+        assert_eq!(pool.values().copied().collect::<Vec<_>>(), vec![1, 3, 5]);
+        for value in pool.values_mut() {
+            *value *= 10;
+        }
+        assert_eq!(pool.iter().map(|(_, v)| *v).sum::<i32>(), 90);
+        assert!(!pool.is_empty());
+    }
+}
@@ -0,0 +1,26 @@
+use macroquad::input::utils::{register_input_subscriber, repeat_all_miniquad_input};
+use macroquad::miniquad::EventHandler;
+// Śledzenie utraty fokusu okna - miniquad zgłasza ją jako minimalizację okna
+pub struct FocusWatcher {
+    subscriber: usize,
+    lost: bool,
+}
+impl FocusWatcher {
+    pub fn new() -> FocusWatcher {
+        FocusWatcher { subscriber: register_input_subscriber(), lost: false }
+    }
+    // Wywoływane co klatkę; zwraca true, jeśli od poprzedniej klatki okno straciło fokus
+    pub fn lost_focus(&mut self) -> bool {
+        self.lost = false;
+        let subscriber = self.subscriber;
+        repeat_all_miniquad_input(self, subscriber);
+        self.lost
+    }
+}
+impl EventHandler for FocusWatcher {
+    fn update(&mut self) {}
+    fn draw(&mut self) {}
+    fn window_minimized_event(&mut self) {
+        self.lost = true;
+    }
+}
@@ -0,0 +1,217 @@
+use macroquad::prelude::Vec2;
+use crate::controls::Action;
+use crate::world::Input;
+// Wychylenie gałki poniżej tej wartości jest ignorowane
+pub const DEAD_ZONE: f32 = 0.25;
+// Wychylenie w osi (po martwej strefie), od którego gałka działa jak kierunek
+const AXIS_THRESHOLD: f32 = 0.3;
+// Przyciski pada (układ Xbox: South = A, East = B, West = X, North = Y)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Button {
+    South,
+    East,
+    West,
+    North,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+// Kierunki i przyciski nawigacji po menu
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Nav {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+}
+// Stan pada w jednej klatce; y gałki rośnie w dół, jak na ekranie
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct PadState {
+    pub stick: Vec2,
+    buttons: u16,
+}
+impl PadState {
+    // Bez feature "gamepad" stan tworzą tylko testy
+    #[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+    pub fn new(stick: Vec2, buttons: &[Button]) -> PadState {
+        let buttons = buttons.iter().fold(0, |bits, &b| bits | 1 << b as u16);
+        PadState { stick, buttons }
+    }
+    pub fn is_down(&self, button: Button) -> bool {
+        self.buttons & 1 << button as u16 != 0
+    }
+}
+// Źródło stanu pada - prawdziwe urządzenie albo atrapa w testach
+pub trait GamepadDevice {
+    // None, gdy żaden pad nie jest podłączony
+    fn poll(&mut self) -> Option<PadState>;
+}
+// Brak obsługi padów (build bez feature "gamepad")
+pub struct NoGamepad;
+impl GamepadDevice for NoGamepad {
+    fn poll(&mut self) -> Option<PadState> {
+        None
+    }
+}
+// Wychylenie gałki z martwą strefą; poza nią przeskalowane tak, żeby zaczynało się od zera
+pub fn apply_dead_zone(stick: Vec2) -> Vec2 {
+    let length = stick.length();
+    if length < DEAD_ZONE {
+        return Vec2::ZERO;
+    }
+    let scaled = ((length - DEAD_ZONE) / (1.0 - DEAD_ZONE)).min(1.0);
+    stick / length * scaled
+}
+// Pad przełożony na akcje gry; stan jest odczytywany raz na klatkę
+pub struct Gamepad {
+    device: Box<dyn GamepadDevice>,
+    state: PadState,
+    previous: PadState,
+}
+impl Gamepad {
+    pub fn new(device: Box<dyn GamepadDevice>) -> Gamepad {
+        Gamepad { device, state: PadState::default(), previous: PadState::default() }
+    }
+    pub fn update(&mut self) {
+        self.previous = self.state;
+        self.state = self.device.poll().unwrap_or_default();
+    }
+    fn action_down(state: &PadState, action: Action) -> bool {
+        let stick = apply_dead_zone(state.stick);
+        match action {
+            Action::MoveLeft => stick.x < -AXIS_THRESHOLD || state.is_down(Button::DPadLeft),
+            Action::MoveRight => stick.x > AXIS_THRESHOLD || state.is_down(Button::DPadRight),
+            Action::MoveUp => stick.y < -AXIS_THRESHOLD || state.is_down(Button::DPadUp),
+            Action::MoveDown => stick.y > AXIS_THRESHOLD || state.is_down(Button::DPadDown),
+            Action::Fire => state.is_down(Button::South) || state.is_down(Button::West),
+            Action::Bomb => state.is_down(Button::East) || state.is_down(Button::North),
+            Action::Pause => state.is_down(Button::Start),
+        }
+    }
+    pub fn down(&self, action: Action) -> bool {
+        Gamepad::action_down(&self.state, action)
+    }
+    pub fn pressed(&self, action: Action) -> bool {
+        self.down(action) && !Gamepad::action_down(&self.previous, action)
+    }
+    // Nawigacja po menu: D-pad, A zatwierdza, B cofa
+    pub fn nav_pressed(&self, nav: Nav) -> bool {
+        let button = match nav {
+            Nav::Up => Button::DPadUp,
+            Nav::Down => Button::DPadDown,
+            Nav::Left => Button::DPadLeft,
+            Nav::Right => Button::DPadRight,
+            Nav::Confirm => Button::South,
+            Nav::Back => Button::East,
+        };
+        self.state.is_down(button) && !self.previous.is_down(button)
+    }
+    // Stan wejścia dla symulacji
+    pub fn read_input(&self) -> Input {
+        Input {
+            left: self.down(Action::MoveLeft),
+            right: self.down(Action::MoveRight),
+            up: self.down(Action::MoveUp),
+            down: self.down(Action::MoveDown),
+            fire: self.down(Action::Fire),
+        }
+    }
+}
+// Pierwszy podłączony pad; bez feature "gamepad" zawsze pusty
+pub fn open_gamepad() -> Gamepad {
+    #[cfg(feature = "gamepad")]
+    if let Some(device) = device::Gilrs::open() {
+        return Gamepad::new(Box::new(device));
+    }
+    Gamepad::new(Box::new(NoGamepad))
+}
+// Obsługa padów przez gilrs
+#[cfg(feature = "gamepad")]
+mod device {
+    use super::{Button, GamepadDevice, PadState};
+    use gilrs::{Axis, Button as GilrsButton};
+    use macroquad::prelude::vec2;
+    pub struct Gilrs {
+        gilrs: gilrs::Gilrs,
+    }
+    impl Gilrs {
+        pub fn open() -> Option<Gilrs> {
+            gilrs::Gilrs::new().ok().map(|gilrs| Gilrs { gilrs })
+        }
+    }
+    impl GamepadDevice for Gilrs {
+        fn poll(&mut self) -> Option<PadState> {
+            while self.gilrs.next_event().is_some() {}
+            let (_, pad) = self.gilrs.gamepads().find(|(_, pad)| pad.is_connected())?;
+            let mapping = [
+                (GilrsButton::South, Button::South),
+                (GilrsButton::East, Button::East),
+                (GilrsButton::West, Button::West),
+                (GilrsButton::North, Button::North),
+                (GilrsButton::Start, Button::Start),
+                (GilrsButton::DPadUp, Button::DPadUp),
+                (GilrsButton::DPadDown, Button::DPadDown),
+                (GilrsButton::DPadLeft, Button::DPadLeft),
+                (GilrsButton::DPadRight, Button::DPadRight),
+            ];
+            let buttons: Vec<Button> = mapping.iter().filter(|(b, _)| pad.is_pressed(*b)).map(|&(_, b)| b).collect();
+            // W gilrs oś Y gałki rośnie w górę
+            let stick = vec2(pad.value(Axis::LeftStickX), -pad.value(Axis::LeftStickY));
+            Some(PadState::new(stick, &buttons))
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macroquad::prelude::vec2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // Atrapa pada sterowana z testu
+    struct FakePad(Rc<RefCell<Option<PadState>>>);
+    impl GamepadDevice for FakePad {
+        fn poll(&mut self) -> Option<PadState> {
+            *self.0.borrow()
+        }
+    }
+
+    fn fake() -> (Gamepad, Rc<RefCell<Option<PadState>>>) {
+        let state = Rc::new(RefCell::new(None));
+        (Gamepad::new(Box::new(FakePad(state.clone()))), state)
+    }
+
+    #[test]
+    fn stick_respects_dead_zone() {
+        assert_eq!(apply_dead_zone(vec2(0.2, -0.1)), Vec2::ZERO);
+        assert_eq!(apply_dead_zone(vec2(1.0, 0.0)), vec2(1.0, 0.0));
+        assert!((apply_dead_zone(vec2(0.0, 0.625)).y - 0.5).abs() < 1e-6);
+        let (mut pad, state) = fake();
+        *state.borrow_mut() = Some(PadState::new(vec2(0.1, 0.9), &[]));
+        pad.update();
+        let input = pad.read_input();
+        assert!(input.down && !input.up && !input.left && !input.right);
+    }
+
+    #[test]
+    fn buttons_map_to_actions() {
+        let (mut pad, state) = fake();
+        *state.borrow_mut() = Some(PadState::new(Vec2::ZERO, &[Button::South, Button::Start, Button::DPadLeft]));
+        pad.update();
+        assert!(pad.down(Action::Fire) && pad.down(Action::MoveLeft));
+        assert!(pad.pressed(Action::Pause) && !pad.down(Action::Bomb));
+        assert!(pad.nav_pressed(Nav::Left) && pad.nav_pressed(Nav::Confirm));
+        // Przytrzymany przycisk nie jest ponownie "wciśnięty"
+        pad.update();
+        assert!(pad.down(Action::Pause) && !pad.pressed(Action::Pause));
+        assert!(!pad.nav_pressed(Nav::Left));
+        // Odłączony pad nie daje wejścia
+        *state.borrow_mut() = None;
+        pad.update();
+        assert!(!pad.down(Action::Fire));
+    }
+}
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+// Liczba wyników w jednej tabeli
+pub const TABLE_SIZE: usize = 10;
+const HIGH_SCORES_FILE: &str = "highscores.json";
+// Tryb gry - każdy ma osobne tabele wyników
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GameMode {
+    #[default]
+    Normal,
+    Daily,
+}
+impl GameMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            GameMode::Normal => "Normal",
+            GameMode::Daily => "Daily Challenge",
+        }
+    }
+}
+// Poziom trudności jako klucz tabeli (w dziesiątych częściach, 1.0 -> 10)
+pub fn difficulty_preset(difficulty: f32) -> u32 {
+    (difficulty * 10.0).round().max(0.0) as u32
+}
+// Pojedynczy wpis tabeli
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ScoreEntry {
+    pub name: String,
+    pub score: i32,
+    pub level: u32,
+    pub seed: u64,
+    pub date: u64, // sekundy od 1970-01-01
+}
+// Klucz tabeli: tryb i poziom trudności, a w wyzwaniu dnia także seed dnia - wyniki z różnych
+// dni nie są ze sobą porównywane
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TableKey {
+    pub mode: GameMode,
+    pub difficulty: u32,
+    #[serde(default)]
+    pub seed: u64, // w trybie Normal zawsze 0
+}
+impl TableKey {
+    pub fn new(mode: GameMode, difficulty: f32, seed: u64) -> TableKey {
+        let seed = if mode == GameMode::Daily { seed } else { 0 };
+        TableKey { mode, difficulty: difficulty_preset(difficulty), seed }
+    }
+}
+// Tabela dla jednego klucza, posortowana malejąco
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ScoreTable {
+    #[serde(flatten)]
+    pub key: TableKey,
+    pub entries: Vec<ScoreEntry>,
+}
+#[derive(Serialize, Deserialize, Default)]
+pub struct HighScores {
+    pub tables: Vec<ScoreTable>,
+}
+impl HighScores {
+    pub fn table(&self, key: TableKey) -> &[ScoreEntry] {
+        self.tables.iter().find(|t| t.key == key).map_or(&[], |t| &t.entries)
+    }
+    // Czy wynik trafi do tabeli
+    pub fn qualifies(&self, key: TableKey, score: i32) -> bool {
+        let table = self.table(key);
+        score > 0 && (table.len() < TABLE_SIZE || table.iter().any(|e| score > e.score))
+    }
+    // Dodaje wynik; zwraca miejsce w tabeli (od 0) albo None, jeśli się nie zmieścił
+    pub fn insert(&mut self, key: TableKey, entry: ScoreEntry) -> Option<usize> {
+        if !self.qualifies(key, entry.score) {
+            return None;
+        }
+        let index = match self.tables.iter().position(|t| t.key == key) {
+            Some(index) => index,
+            None => {
+                self.tables.push(ScoreTable { key, entries: vec![] });
+                self.tables.len() - 1
+            }
+        };
+        let entries = &mut self.tables[index].entries;
+        // Przy remisie starszy wynik zostaje wyżej
+        let rank = entries.iter().position(|e| entry.score > e.score).unwrap_or(entries.len());
+        entries.insert(rank, entry);
+        entries.truncate(TABLE_SIZE);
+        Some(rank)
+    }
+    pub fn rename(&mut self, key: TableKey, rank: usize, name: &str) {
+        if let Some(entry) = self.tables.iter_mut().find(|t| t.key == key).and_then(|t| t.entries.get_mut(rank)) {
+            entry.name = name.to_string();
+        }
+    }
+    // Klucze tabel danego trybu: Normal według poziomu trudności, wyzwania dnia od najstarszego
+    pub fn keys(&self, mode: GameMode) -> Vec<TableKey> {
+        let mut tables: Vec<&ScoreTable> = self.tables.iter().filter(|t| t.key.mode == mode).collect();
+        match mode {
+            GameMode::Normal => tables.sort_by_key(|t| t.key.difficulty),
+            GameMode::Daily => tables.sort_by_key(|t| t.entries.iter().map(|e| e.date).min()),
+        }
+        tables.iter().map(|t| t.key).collect()
+    }
+    // Dawne tabele wyzwania dnia (bez seeda) łączyły wszystkie dni - wpisy wracają do tabel swoich dni
+    fn split_old_daily_tables(&mut self) {
+        let (old, tables): (Vec<ScoreTable>, Vec<ScoreTable>) =
+            std::mem::take(&mut self.tables).into_iter().partition(|t| t.key.mode == GameMode::Daily && t.key.seed == 0);
+        self.tables = tables;
+        for table in old {
+            for entry in table.entries {
+                self.insert(TableKey { seed: entry.seed, ..table.key }, entry);
+            }
+        }
+    }
+}
+// Funkcja zapisu tabel wyników
+pub fn save_high_scores(high_scores: &HighScores) {
+    let serialized = serde_json::to_string(high_scores).unwrap();
+    fs::write(HIGH_SCORES_FILE, serialized).unwrap_or(());
+}
+// Funkcja wczytywania tabel wyników
+pub fn load_high_scores() -> HighScores {
+    match fs::read_to_string(HIGH_SCORES_FILE) {
+        Ok(data) => {
+            let mut high_scores: HighScores = serde_json::from_str(&data).unwrap_or_default();
+            high_scores.split_old_daily_tables();
+            high_scores
+        }
+        Err(_) => import_legacy_high_score(),
+    }
+}
+// Rekord z dawnego settings.json: pierwsza wersja zapisywała tylko `difficulty`, `sound_volume`
+// i `high_score`, a ekran końca gry dopisał później `high_score_name`
+#[derive(Deserialize)]
+struct LegacySettings {
+    difficulty: f32,
+    high_score: i32,
+    #[serde(default)]
+    high_score_name: String,
+}
+fn import_legacy_high_score() -> HighScores {
+    fs::read_to_string("settings.json").map(|data| legacy_high_score(&data)).unwrap_or_default()
+}
+// Rekord trafia do tabeli trybu Normal; bez zapisanej nazwy dostaje domyślną
+fn legacy_high_score(data: &str) -> HighScores {
+    let mut high_scores = HighScores::default();
+    if let Ok(legacy) = serde_json::from_str::<LegacySettings>(data) {
+        let name = if legacy.high_score_name.is_empty() { "Player".to_string() } else { legacy.high_score_name };
+        let entry = ScoreEntry { name, score: legacy.high_score, level: 0, seed: 0, date: 0 };
+        high_scores.insert(TableKey::new(GameMode::Normal, legacy.difficulty, 0), entry);
+    }
+    high_scores
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NORMAL: TableKey = TableKey { mode: GameMode::Normal, difficulty: 10, seed: 0 };
+
+    fn entry(score: i32) -> ScoreEntry {
+        ScoreEntry { name: format!("P{}", score), score, level: 1, seed: 0, date: 0 }
+    }
+
+    #[test]
+    fn keeps_top_ten_sorted() {
+        let mut scores = HighScores::default();
+        for score in [50, 10, 90, 30, 70, 20, 80, 40, 60, 100, 5] {
+            scores.insert(NORMAL, entry(score));
+        }
+        let table: Vec<i32> = scores.table(NORMAL).iter().map(|e| e.score).collect();
+        assert_eq!(table, vec![100, 90, 80, 70, 60, 50, 40, 30, 20, 10]);
+        assert!(!scores.qualifies(NORMAL, 10));
+        assert_eq!(scores.insert(NORMAL, entry(95)), Some(1));
+        assert_eq!(scores.insert(NORMAL, entry(1)), None);
+    }
+
+    #[test]
+    fn imports_legacy_high_score() {
+        // Pierwszy format ustawień - bez nazwy gracza
+        let scores = legacy_high_score(r#"{"difficulty":1.5,"sound_volume":0.5,"high_score":420}"#);
+        assert_eq!(scores.table(TableKey { difficulty: 15, ..NORMAL }), [ScoreEntry { name: "Player".to_string(), score: 420, level: 0, seed: 0, date: 0 }]);
+        let scores = legacy_high_score(r#"{"difficulty":1.0,"sound_volume":0.5,"high_score":90,"high_score_name":"Ada"}"#);
+        assert_eq!(scores.table(NORMAL)[0].name, "Ada");
+        // Bez rekordu nie ma czego przenosić
+        assert!(legacy_high_score(r#"{"difficulty":1.0,"sound_volume":0.5,"high_score":0}"#).tables.is_empty());
+        assert!(legacy_high_score(r#"{"difficulty":1.0,"master_volume":0.5}"#).tables.is_empty());
+    }
+
+    #[test]
+    fn tables_are_separate_per_mode_difficulty_and_day() {
+        let mut scores = HighScores::default();
+        let today = TableKey::new(GameMode::Daily, 1.0, 7);
+        let yesterday = TableKey::new(GameMode::Daily, 1.0, 6);
+        assert_eq!(scores.insert(NORMAL, entry(100)), Some(0));
+        assert_eq!(scores.insert(today, entry(50)), Some(0));
+        assert_eq!(scores.insert(yesterday, entry(80)), Some(0));
+        assert_eq!(scores.insert(TableKey::new(GameMode::Normal, 1.5, 99), entry(20)), Some(0));
+        assert_eq!(scores.table(NORMAL).len(), 1);
+        assert_eq!(scores.table(today).len(), 1);
+        assert_eq!(scores.keys(GameMode::Normal), vec![NORMAL, TableKey { difficulty: 15, ..NORMAL }]);
+        scores.rename(today, 0, "Ada");
+        assert_eq!(scores.table(today)[0].name, "Ada");
+        assert_eq!(scores.table(yesterday)[0].name, "P80");
+    }
+
+    #[test]
+    fn old_daily_table_is_split_by_day() {
+        let data = r#"{"tables":[{"mode":"Daily","difficulty":10,"entries":[
+            {"name":"A","score":90,"level":3,"seed":7,"date":200},
+            {"name":"B","score":60,"level":2,"seed":6,"date":100},
+            {"name":"C","score":40,"level":2,"seed":7,"date":210}]}]}"#;
+        let mut scores: HighScores = serde_json::from_str(data).unwrap();
+        scores.split_old_daily_tables();
+        let days = scores.keys(GameMode::Daily);
+        assert_eq!(days, vec![TableKey::new(GameMode::Daily, 1.0, 6), TableKey::new(GameMode::Daily, 1.0, 7)]);
+        let names: Vec<&str> = scores.table(days[1]).iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["A", "C"]);
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+// Rodzaje obiektów gry - indeksy w listach definicji (`defs::Definitions`)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct EnemyKind(pub usize);
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct BulletKind(pub usize);
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct PowerUpKind(pub usize);
+// Rodzaj w zapisie gry: nazwa z definicji albo - w starszych zapisach - numer (kolejność wbudowanego zestawu)
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum KindName {
+    Index(u8),
+    Name(String),
+}
+impl KindName {
+    // Indeks w liście definicji o podanych nazwach; None, jeśli takiego rodzaju już nie ma.
+    // Numer zamieniany jest najpierw na nazwę z wbudowanego zestawu - definicje mogły zmienić kolejność
+    pub fn resolve<'a>(&self, built_in: impl IntoIterator<Item = &'a str>, names: impl IntoIterator<Item = &'a str>) -> Option<usize> {
+        let name = match self {
+            KindName::Index(index) => built_in.into_iter().nth(*index as usize)?,
+            KindName::Name(name) => name.as_str(),
+        };
+        names.into_iter().position(|candidate| candidate == name)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_read_old_numbers_and_names() {
+        let built_in = ["Normal", "Fast", "Tank", "Shooter"];
+        // Definicje po zmianie kolejności i usunięciu rodzaju "Fast"
+        let names = ["Shooter", "Normal", "Tank"];
+        let kinds: Vec<KindName> = serde_json::from_str(r#"[0, 3, "Tank", 1, 7, "Laser"]"#).unwrap();
+        let resolved: Vec<Option<usize>> = kinds.iter().map(|kind| kind.resolve(built_in, names)).collect();
+        assert_eq!(resolved, [Some(1), Some(0), Some(2), None, None, None]);
+        assert_eq!(serde_json::to_string(&KindName::Name("Shooter".to_string())).unwrap(), r#""Shooter""#);
+    }
+}
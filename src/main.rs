@@ -1,568 +1,754 @@
 use macroquad::prelude::*;
-use serde::{Deserialize, Serialize};
-use std::fs;
-use ::rand::thread_rng;
-use ::rand::Rng;
-// Serializable Vec2
-#[derive(Serialize, Deserialize, Copy, Clone)]
-struct SerVec2 {
-    x: f32,
-    y: f32,
-}
-// Serializable Enemy
-#[derive(Serialize, Deserialize)]
-struct SerEnemy {
-    pos: SerVec2,
-    speed: f32,
-    alive: bool,
-    enemy_type: u8, // 0: normal, 1: fast, 2: tank, 3: shooter
-    health: i32,
-    last_shot: f64,
-}
-// Serializable Bullet
-#[derive(Serialize, Deserialize)]
-struct SerBullet {
-    pos: SerVec2,
-    vel: SerVec2,
-    alive: bool,
-    bullet_type: u8, // 0: normal, 1: power-up
-}
-// Serializable PowerUp
-#[derive(Serialize, Deserialize)]
-struct SerPowerUp {
-    pos: SerVec2,
-    speed: f32,
-    alive: bool,
-    power_type: u8, // 0: health, 1: multi shot
-}
-// Struktura zapisu gry
-#[derive(Serialize, Deserialize)]
-struct GameSave {
-    score: i32,
-    player_pos: SerVec2,
-    difficulty: f32,
-    enemies: Vec<SerEnemy>,
-    bullets: Vec<SerBullet>,
-    enemy_bullets: Vec<SerBullet>,
-    power_ups: Vec<SerPowerUp>,
-    player_health: i32,
-    level: u32,
-    shot_level: u32,
-}
-// Struktura ustawień
-#[derive(Serialize, Deserialize)]
-struct Settings {
-    difficulty: f32,
-    sound_volume: f32,
-    high_score: i32,
-}
+mod audio;
+mod canvas;
+mod collision;
+mod controls;
+mod date;
+mod defs;
+mod display;
+mod entity;
+mod focus;
+mod gamepad;
+mod highscores;
+mod kinds;
+mod menu;
+mod replay;
+mod rng;
+mod save;
+// Syntezator jest potrzebny tylko z prawdziwym dźwiękiem
+#[cfg_attr(not(feature = "audio"), allow(dead_code))]
+mod synth;
+mod timestep;
+mod ui;
+mod world;
+use audio::{open_audio, Sfx};
+use canvas::{Canvas, PLAYFIELD};
+use collision::PLAYER_SIZE;
+use controls::{Action, Bindings, Key};
+use date::{civil_from_days, format_duration, format_timestamp, now_secs, today};
+use display::FrameLimiter;
+use focus::FocusWatcher;
+use gamepad::{open_gamepad, Gamepad, Nav};
+use menu::{MenuFocus, MenuInput};
+use highscores::{load_high_scores, save_high_scores, GameMode, ScoreEntry, TableKey, TABLE_SIZE};
+use replay::{list_replays, save_replay, Playback, Replay};
+use rng::{daily_seed, random_seed};
+use save::{default_slot_name, delete_slot, list_slots, load_game, load_settings, rename_slot, save_game, save_settings, slot_for_new_game, GameSave, LoadError, SLOT_COUNT};
+use timestep::{FixedStep, TICK};
+use ui::{Cell, ListItem, ListState, Screen, TextEvent, Ui, UiInput};
+use world::{Input, World};
 // Stany gry
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 enum GameState {
     Menu,
     Game,
+    Paused,
     Settings,
+    Controls,
     LoadGame,
+    HighScores,
+    Replays,
+    Replay,
+    GameOver,
 }
-// Struktura wroga
-struct Enemy {
-    pos: Vec2,
-    speed: f32,
-    alive: bool,
-    enemy_type: u8, // 0: normal, 1: fast, 2: tank, 3: shooter
-    health: i32,
-    last_shot: f64,
+// Bieżąca rozgrywka: symulacja, jej zegar, nagranie i slot zapisu
+struct Session {
+    clock: FixedStep,
+    world: World,
+    // Nagrywana jest tylko gra rozpoczęta od zera (wczytany zapis nie ma nagrania)
+    recording: Option<Replay>,
+    slot: Option<usize>, // nowa gra dostaje slot dopiero przy pierwszym zapisie
+    slot_name: String,
+    mode: GameMode,
 }
-// Struktura pocisku
-struct Bullet {
-    pos: Vec2,
-    vel: Vec2,
-    alive: bool,
-    bullet_type: u8, // 0: normal, 1: power-up (silniejszy)
+impl Session {
+    // Nowa gra; wyzwanie dnia ma wspólny seed i stałą trudność, żeby wyniki były porównywalne
+    fn new(difficulty: f32, mode: GameMode) -> Session {
+        let (difficulty, seed) = match mode {
+            GameMode::Normal => (difficulty, random_seed()),
+            GameMode::Daily => (DAILY_DIFFICULTY, daily_seed(today(), defs::current().fingerprint())),
+        };
+        let world = World::new(PLAYFIELD, difficulty, seed);
+        Session {
+            recording: Some(Replay::start(&world)),
+            clock: FixedStep::new(),
+            world,
+            slot: None,
+            slot_name: String::new(),
+            mode,
+        }
+    }
+    // Wznowienie gry z zapisu
+    fn from_save(save: GameSave, slot: usize) -> Session {
+        let slot_name = save.name.clone();
+        let mode = save.mode;
+        Session {
+            world: save.into_world(PLAYFIELD),
+            clock: FixedStep::new(),
+            recording: None,
+            slot: Some(slot),
+            slot_name,
+            mode,
+        }
+    }
+    // Kroki symulacji za czas ostatniej klatki
+    fn update(&mut self, input: &Input) {
+        self.clock.accumulate(get_frame_time());
+        while self.clock.tick() && !self.world.is_over() {
+            self.world.step(input, TICK);
+            if let Some(replay) = self.recording.as_mut() {
+                replay.record(input);
+            }
+        }
+    }
+    // Zapis do slotu przy wyjściu z gry i zamknięcie nagrania.
+    // False, gdy nowa gra nie znalazła slotu, który można zająć bez pytania
+    fn save(&mut self) -> bool {
+        self.close_recording(true);
+        if self.slot.is_none() {
+            self.slot = slot_for_new_game();
+            self.slot_name = self.slot.map(default_slot_name).unwrap_or_default();
+        }
+        let Some(slot) = self.slot else { return false };
+        save_game(slot, &self.slot_name, self.mode, &self.world);
+        true
+    }
+    // Koniec gry śmiercią: zapis w slocie tej gry zostaje oznaczony jako zakończony.
+    // Gra, która nie była zapisana, nie zajmuje slotu - zapisałaby tylko niewznawialny wynik
+    fn finish(&mut self) {
+        self.close_recording(false);
+        if let Some(slot) = self.slot {
+            save_game(slot, &self.slot_name, self.mode, &self.world);
+        }
+    }
+    fn close_recording(&mut self, escape: bool) {
+        if let Some(mut replay) = self.recording.take() {
+            replay.finish(self.world.score, escape);
+            save_replay(&replay);
+        }
+    }
+    // Czy grę można jeszcze kontynuować
+    fn is_active(&self) -> bool {
+        !self.world.is_over()
+    }
 }
-// Struktura power-up
-struct PowerUp {
-    pos: Vec2,
-    speed: f32,
-    alive: bool,
-    power_type: u8, // 0: health, 1: multi shot
+// Szerokości kolumn tabeli wyników: nazwa, wynik, poziom, data (seed nie mieści się na planszy)
+const SCORE_COLUMNS: [f32; 4] = [150.0, 60.0, 60.0, 160.0];
+// Stała trudność wyzwania dnia
+const DAILY_DIFFICULTY: f32 = 1.0;
+// Szerokość list (sloty, nagrania)
+const LIST_WIDTH: f32 = 440.0;
+// Okno według zapisanych ustawień; po zmianie rozmiaru obraz jest skalowany
+fn window_conf() -> Conf {
+    load_settings().display.conf()
 }
-#[macroquad::main("StarBlaster")]
+#[macroquad::main(window_conf)]
 async fn main() {
     // Wczytaj ustawienia
     let mut settings = load_settings();
+    // Definicje wrogów i broni; błędny plik nie blokuje gry - zostaje wbudowany zestaw, a błędy
+    // są wypisywane na konsolę
+    let (definitions, definitions_error) = defs::load_definitions();
+    if let Some(error) = &definitions_error {
+        eprintln!("{}", error);
+    }
+    defs::install(definitions);
+    let mut audio = open_audio(&settings).await;
     // Inicjalizacja zmiennych gry
     let mut game_state = GameState::Menu;
-    let mut player_pos = vec2(screen_width() / 2.0, screen_height() - 50.0);
-    let mut player_health = 3;
-    let mut score = 0;
-    let mut enemies: Vec<Enemy> = vec![];
-    let mut bullets: Vec<Bullet> = vec![];
-    let mut enemy_bullets: Vec<Bullet> = vec![];
-    let mut power_ups: Vec<PowerUp> = vec![];
-    let mut last_shot = get_time();
-    let mut level = 1u32;
-    let mut shot_level = 1u32;
-    let mut last_power_up = get_time();
+    let canvas = Canvas::new();
+    let mut screen = Screen;
+    let mut menu = MenuFocus::new();
+    let mut menu_state = game_state;
+    let mut focus = FocusWatcher::new();
+    let mut limiter = FrameLimiter::new();
+    let mut gamepad = open_gamepad();
+    // Ekran, do którego wraca przycisk Back w ustawieniach
+    let mut settings_return = GameState::Menu;
+    // Ekran klawiszy: akcja i miejsce czekające na nowy klawisz, komunikat o konflikcie
+    let mut rebinding: Option<(Action, usize)> = None;
+    let mut controls_message = String::new();
+    let mut session: Option<Session> = None;
+    let mut replays: Vec<(String, Replay)> = vec![];
+    let mut replay_list = ListState::default();
+    let mut playback: Option<Playback> = None;
+    // Stan przeglądarki slotów
+    let mut slots: Vec<Result<GameSave, LoadError>> = vec![];
+    let mut slot_list = ListState::default();
+    let mut renaming: Option<String> = None;
+    let mut confirm_delete = false;
+    let mut slots_message = String::new();
+    // Tabele wyników i przeglądany tryb/poziom trudności
+    let mut high_scores = load_high_scores();
+    let mut scores_key = TableKey::new(GameMode::Normal, settings.difficulty, 0);
+    // Stan ekranu końca gry: miejsce wyniku w tabeli i wpisywana nazwa
+    let mut score_rank: Option<usize> = None;
+    let mut name_entry: Option<String> = None;
+    // Wpisywanie nazwy czeka, aż gracz puści klawisze trzymane w chwili śmierci (inaczej
+    // powtarzane znaki ruchu i strzału trafiłyby do nazwy)
+    let mut name_entry_pending = false;
     loop {
-        clear_background(BLACK);
+        canvas.begin();
+        let focus_lost = focus.lost_focus();
+        gamepad.update();
+        // Nowy ekran zaczyna od pierwszego widżetu; podczas wpisywania tekstu klawisze nie przesuwają fokusu
+        if game_state != menu_state {
+            menu_state = game_state;
+            menu.reset(0);
+        }
+        let typing = renaming.is_some() || name_entry.is_some() || name_entry_pending || rebinding.is_some();
+        menu.begin(if typing { MenuInput::default() } else { read_menu_input(&gamepad) });
+        let ui_input = UiInput::read(Canvas::mouse());
+        audio.configure(&settings);
+        audio.update(get_frame_time(), true);
         match game_state {
             GameState::Menu => {
                 // Menu główne
-                draw_text("StarBlaster", screen_width() / 2.0 - 100.0, 100.0, 40.0, WHITE);
-                draw_text(&format!("High Score: {}", settings.high_score), screen_width() / 2.0 - 100.0, 150.0, 30.0, WHITE);
-                if draw_button("Start", screen_width() / 2.0 - 50.0, 200.0) {
+                let mut ui = Ui::new(&mut screen, &mut menu, &ui_input, 60.0);
+                ui.title("StarBlaster");
+                if definitions_error.is_some() {
+                    ui.label_sized("definitions.json has errors - using built-in set", 16.0, RED);
+                }
+                ui.space(40.0);
+                if ui.button("Start") {
+                    game_state = GameState::Game;
+                    session = Some(Session::new(settings.difficulty, GameMode::Normal));
+                }
+                if ui.button("Daily Challenge") {
                     game_state = GameState::Game;
-                    score = 0;
-                    player_health = 3;
-                    enemies.clear();
-                    bullets.clear();
-                    enemy_bullets.clear();
-                    power_ups.clear();
-                    player_pos = vec2(screen_width() / 2.0, screen_height() - 50.0);
-                    level = 1;
-                    shot_level = 1;
-                }
-                if draw_button("Load Game", screen_width() / 2.0 - 50.0, 250.0) {
+                    session = Some(Session::new(settings.difficulty, GameMode::Daily));
+                }
+                if ui.button("Load Game") {
+                    slots = list_slots();
+                    slot_list = ListState { selected: session.as_ref().and_then(|s| s.slot).unwrap_or(0), scroll: 0 };
+                    renaming = None;
+                    confirm_delete = false;
+                    slots_message.clear();
                     game_state = GameState::LoadGame;
                 }
-                if draw_button("Settings", screen_width() / 2.0 - 50.0, 300.0) {
+                if ui.button("Replays") {
+                    replays = list_replays();
+                    replay_list = ListState::default();
+                    game_state = GameState::Replays;
+                }
+                if ui.button("High Scores") {
+                    scores_key = default_scores_key(scores_key.mode, settings.difficulty);
+                    game_state = GameState::HighScores;
+                }
+                if ui.button("Settings") {
+                    settings_return = GameState::Menu;
                     game_state = GameState::Settings;
                 }
-                if draw_button("Exit", screen_width() / 2.0 - 50.0, 350.0) {
+                if ui.button("Exit") {
                     break;
                 }
             }
             GameState::Game => {
-                // Logika gry
-                // Sterowanie graczem
-                if is_key_down(KeyCode::Left) {
-                    player_pos.x -= 300.0 * get_frame_time();
-                }
-                if is_key_down(KeyCode::Right) {
-                    player_pos.x += 300.0 * get_frame_time();
-                }
-                if is_key_down(KeyCode::Up) {
-                    player_pos.y -= 300.0 * get_frame_time();
-                }
-                if is_key_down(KeyCode::Down) {
-                    player_pos.y += 300.0 * get_frame_time();
-                }
-                // Ograniczenie ruchu gracza
-                player_pos.x = player_pos.x.clamp(0.0, screen_width() - 20.0);
-                player_pos.y = player_pos.y.clamp(0.0, screen_height() - 20.0);
-                // Strzelanie
-                if is_key_down(KeyCode::Space) && get_time() - last_shot > 0.2 {
-                    let bullet_vel = vec2(0.0, -400.0);
-                    let offset_step = 10.0;
-                    let start_offset = -((shot_level - 1) as f32 * offset_step / 2.0);
-                    for i in 0..shot_level {
-                        let offset = start_offset + (i as f32) * offset_step;
-                        bullets.push(Bullet {
-                            pos: vec2(player_pos.x + offset, player_pos.y),
-                                     vel: bullet_vel,
-                                     alive: true,
-                                     bullet_type: if shot_level > 1 { 1 } else { 0 },
-                        });
-                    }
-                    last_shot = get_time();
-                }
-                // Spawn wrogów
-                let level_factor = (level as f32 / 5.0) + 1.0;
-                let spawn_chance = 0.02 * settings.difficulty * level_factor;
-                if thread_rng().gen_range(0.0..1.0) < spawn_chance {
-                    let enemy_type = thread_rng().gen_range(0..4);
-                    let base_speed = match enemy_type {
-                        1 => 150.0,
-                        3 => 80.0,
-                        _ => 100.0,
-                    };
-                    let speed = base_speed * settings.difficulty * (level as f32 / 10.0 + 1.0);
-                    let health = match enemy_type {
-                        2 => 3,
-                        3 => 2,
-                        _ => 1,
+                let Some(current) = session.as_mut() else {
+                    game_state = GameState::Menu;
+                    continue;
+                };
+                // Logika gry (rozmiar areny jest stały przez całą grę, żeby nagranie było powtarzalne)
+                current.update(&read_input(&settings.bindings, &gamepad));
+                audio.play_events(&current.world.take_events());
+                if current.world.is_over() {
+                    // Martwa gra nie jest wznawialna - jej slot zostaje oznaczony jako zakończony
+                    current.finish();
+                    audio.duck(3.0);
+                    // Wynik trafia do tabeli od razu, a nazwę można od razu poprawić
+                    let world = &current.world;
+                    let entry = ScoreEntry {
+                        name: if settings.player_name.is_empty() { "Player".to_string() } else { settings.player_name.clone() },
+                        score: world.score,
+                        level: world.level,
+                        seed: world.seed,
+                        date: now_secs(),
                     };
-                    enemies.push(Enemy {
-                        pos: vec2(thread_rng().gen_range(0.0..screen_width()), 0.0),
-                                 speed,
-                                 alive: true,
-                                 enemy_type,
-                                 health,
-                                 last_shot: if enemy_type == 3 { get_time() } else { 0.0 },
-                    });
-                }
-                // Spawn power-upów
-                if get_time() - last_power_up > 10.0 && thread_rng().gen_range(0.0..1.0) < 0.005 {
-                    let power_type = thread_rng().gen_range(0..2);
-                    power_ups.push(PowerUp {
-                        pos: vec2(thread_rng().gen_range(0.0..screen_width()), 0.0),
-                                   speed: 80.0,
-                                   alive: true,
-                                   power_type,
-                    });
-                    last_power_up = get_time();
-                }
-                // Aktualizacja pocisków gracza
-                let mut bullets_to_kill: Vec<usize> = vec![];
-                for (i, bullet) in bullets.iter_mut().enumerate() {
-                    if bullet.alive {
-                        bullet.pos += bullet.vel * get_frame_time();
-                        if bullet.pos.y < 0.0 || bullet.pos.y > screen_height() || bullet.pos.x < 0.0 || bullet.pos.x > screen_width() {
-                            bullets_to_kill.push(i);
-                        }
+                    score_rank = high_scores.insert(TableKey::new(current.mode, world.difficulty, world.seed), entry);
+                    if score_rank.is_some() {
+                        save_high_scores(&high_scores);
                     }
+                    name_entry = None;
+                    name_entry_pending = score_rank.is_some();
+                    game_state = GameState::GameOver;
                 }
-                // Aktualizacja pocisków wrogów
-                let mut enemy_bullets_to_kill: Vec<usize> = vec![];
-                for (i, bullet) in enemy_bullets.iter_mut().enumerate() {
-                    if bullet.alive {
-                        bullet.pos += bullet.vel * get_frame_time();
-                        if bullet.pos.y < 0.0 || bullet.pos.y > screen_height() || bullet.pos.x < 0.0 || bullet.pos.x > screen_width() {
-                            enemy_bullets_to_kill.push(i);
-                        }
-                    }
-                }
-                // Aktualizacja wrogów
-                let mut enemies_to_kill: Vec<usize> = vec![];
-                for (i, enemy) in enemies.iter_mut().enumerate() {
-                    if enemy.alive {
-                        enemy.pos.y += enemy.speed * get_frame_time();
-                        if enemy.pos.y > screen_height() {
-                            enemies_to_kill.push(i);
-                        }
-                        if enemy.enemy_type == 3 && get_time() - enemy.last_shot > 1.5 - ((level as f64 / 20.0).min(1.0)) {
-                            let direction = (player_pos - enemy.pos).normalize_or_zero();
-                            let bullet_speed = 200.0 * settings.difficulty * (level as f32 / 10.0 + 1.0);
-                            enemy_bullets.push(Bullet {
-                                pos: enemy.pos + vec2(10.0, 10.0),
-                                               vel: direction * bullet_speed,
-                                               alive: true,
-                                               bullet_type: 0,
-                            });
-                            enemy.last_shot = get_time();
-                        }
-                    }
+                // Rysowanie
+                draw_world(&canvas, &current.world, current.clock.alpha());
+                // Pauza (także automatycznie po utracie fokusu okna)
+                if (settings.bindings.pressed(Action::Pause) || gamepad.pressed(Action::Pause) || focus_lost) && current.is_active() {
+                    game_state = GameState::Paused;
                 }
-                // Aktualizacja power-upów
-                let mut power_ups_to_kill = vec![];
-                for (i, power_up) in power_ups.iter_mut().enumerate() {
-                    if power_up.alive {
-                        power_up.pos.y += power_up.speed * get_frame_time();
-                        if power_up.pos.y > screen_height() {
-                            power_ups_to_kill.push(i);
-                        }
+            }
+            GameState::Paused => {
+                // Pauza: symulacja i jej zegar stoją, więc liczniki strzałów i power-upów nie upływają
+                let Some(current) = session.as_mut() else {
+                    game_state = GameState::Menu;
+                    continue;
+                };
+                draw_world(&canvas, &current.world, current.clock.alpha());
+                draw_rectangle(0.0, 0.0, PLAYFIELD.x, PLAYFIELD.y, Color::new(0.0, 0.0, 0.0, 0.6));
+                let mut ui = Ui::new(&mut screen, &mut menu, &ui_input, 120.0);
+                ui.title("Paused");
+                if ui.button("Resume") || settings.bindings.pressed(Action::Pause)
+                    || gamepad.pressed(Action::Pause)
+                    || nav_pressed(&gamepad, Nav::Back)
+                {
+                    game_state = GameState::Game;
+                } else if ui.button("Settings") {
+                    settings_return = GameState::Paused;
+                    game_state = GameState::Settings;
+                } else if ui.button("Save & Quit") {
+                    if current.save() {
+                        game_state = GameState::Menu;
+                    } else {
+                        // Wszystkie sloty zajmują gry w toku - gracz sam wybiera, którą nadpisać
+                        slots = list_slots();
+                        slot_list = ListState::default();
+                        renaming = None;
+                        confirm_delete = false;
+                        slots_message = "All slots are in use - overwrite one or go back".to_string();
+                        game_state = GameState::LoadGame;
                     }
+                } else if ui.button("Restart") {
+                    session = Some(Session::new(current.world.difficulty, current.mode));
+                    game_state = GameState::Game;
                 }
-                // Kolizje pocisków gracza z wrogami
-                let mut collisions = vec![];
-                for (b_idx, bullet) in bullets.iter().enumerate() {
-                    if bullet.alive {
-                        for (e_idx, enemy) in enemies.iter_mut().enumerate() {
-                            if enemy.alive && (bullet.pos - enemy.pos).length() < 20.0 {
-                                let damage = if bullet.bullet_type == 1 { 2 } else { 1 };
-                                enemy.health -= damage;
-                                if enemy.health <= 0 {
-                                    collisions.push((b_idx, e_idx));
-                                    score += 10 * (enemy.enemy_type as i32 + 1);
-                                } else {
-                                    bullets_to_kill.push(b_idx);
-                                }
+            }
+            GameState::GameOver => {
+                // Ekran końca gry
+                let Some(current) = session.as_ref() else {
+                    game_state = GameState::Menu;
+                    continue;
+                };
+                let world = &current.world;
+                let mut ui = Ui::new(&mut screen, &mut menu, &ui_input, 70.0);
+                ui.label_sized("Game Over", 40.0, RED);
+                ui.label_sized(&format!("Final Score: {}", world.score), 30.0, WHITE);
+                ui.label(&format!("Level Reached: {}", world.level), WHITE);
+                ui.label_sized(
+                    &format!(
+                        "Kills - {}",
+                        world.defs.enemies.iter().zip(&world.kills).map(|(def, kills)| format!("{}: {}", def.name, kills)).collect::<Vec<_>>().join(", ")
+                    ),
+                    16.0,
+                    WHITE,
+                );
+                match score_rank {
+                    Some(0) => ui.label_sized("New High Score!", 30.0, GOLD),
+                    Some(rank) => ui.label_sized(&format!("Top {}! Rank #{}", TABLE_SIZE, rank + 1), 30.0, GOLD),
+                    None => {}
+                }
+                let key = TableKey::new(current.mode, world.difficulty, world.seed);
+                if name_entry_pending && get_keys_down().is_empty() {
+                    name_entry_pending = false;
+                    name_entry = Some(settings.player_name.clone());
+                }
+                if name_entry_pending {
+                    ui.label_sized("Release all keys to enter your name", 16.0, GRAY);
+                } else if let Some(buffer) = name_entry.as_mut() {
+                    // Wpisywanie nazwy do tabeli wyników
+                    let event = ui.text_input("Name", buffer, 16);
+                    ui.label_sized("Enter: confirm, Escape: keep previous name", 16.0, GRAY);
+                    match event {
+                        TextEvent::Submitted => {
+                            let name = buffer.trim().to_string();
+                            if let (Some(rank), false) = (score_rank, name.is_empty()) {
+                                high_scores.rename(key, rank, &name);
+                                save_high_scores(&high_scores);
+                                settings.player_name = name;
+                                save_settings(&settings);
                             }
+                            name_entry = None;
                         }
+                        TextEvent::Cancelled => name_entry = None,
+                        TextEvent::Editing => {}
                     }
-                }
-                for (b_idx, e_idx) in collisions {
-                    bullets[b_idx].alive = false;
-                    enemies[e_idx].alive = false;
-                }
-                // Kolizje pocisków wrogów z graczem
-                let mut player_hit_by_bullet = false;
-                for (i, bullet) in enemy_bullets.iter().enumerate() {
-                    if bullet.alive && (player_pos - bullet.pos).length() < 15.0 {
-                        enemy_bullets_to_kill.push(i);
-                        player_hit_by_bullet = true;
-                    }
-                }
-                if player_hit_by_bullet {
-                    player_health -= 1;
-                    if player_health <= 0 {
-                        if score > settings.high_score {
-                            settings.high_score = score;
-                            save_settings(&settings);
-                        }
-                        save_game(score, player_pos, settings.difficulty, &enemies, &bullets, &enemy_bullets, &power_ups, player_health, level, shot_level);
-                        game_state = GameState::Menu;
-                    }
-                }
-                // Kolizje gracza z wrogami
-                let mut player_hit = false;
-                for (i, enemy) in enemies.iter().enumerate() {
-                    if enemy.alive && (player_pos - enemy.pos).length() < 20.0 {
-                        enemies_to_kill.push(i);
-                        player_hit = true;
-                    }
-                }
-                if player_hit {
-                    player_health -= 1;
-                    if player_health <= 0 {
-                        if score > settings.high_score {
-                            settings.high_score = score;
-                            save_settings(&settings);
-                        }
-                        save_game(score, player_pos, settings.difficulty, &enemies, &bullets, &enemy_bullets, &power_ups, player_health, level, shot_level);
+                } else {
+                    ui.space(10.0);
+                    if ui.button("Retry") {
+                        session = Some(Session::new(world.difficulty, current.mode));
+                        game_state = GameState::Game;
+                    } else if ui.button("Menu") || nav_pressed(&gamepad, Nav::Back) {
                         game_state = GameState::Menu;
+                    } else if score_rank.is_some() && (ui.button("Enter name") || is_key_pressed(KeyCode::N)) {
+                        name_entry = score_rank
+                            .and_then(|rank| high_scores.table(key).get(rank))
+                            .map(|e| e.name.clone());
                     }
                 }
-                // Kolizje gracza z power-upami
-                for (i, power_up) in power_ups.iter().enumerate() {
-                    if power_up.alive && (player_pos - power_up.pos).length() < 20.0 {
-                        power_ups_to_kill.push(i);
-                        match power_up.power_type {
-                            0 => player_health = (player_health + 1).min(5),
-                            1 => shot_level = (shot_level + 1).min(5),
-                            _ => {},
-                        }
+            }
+            GameState::HighScores => {
+                // Tabela wyników dla wybranego trybu i poziomu trudności (wyzwanie dnia - dla wybranego dnia)
+                let mut ui = Ui::new(&mut screen, &mut menu, &ui_input, 40.0);
+                ui.title("High Scores");
+                let table = high_scores.table(scores_key);
+                ui.label(&scores_title(scores_key, table), WHITE);
+                ui.space(10.0);
+                if table.is_empty() {
+                    ui.label("No scores yet!", GRAY);
+                }
+                for (i, entry) in table.iter().enumerate() {
+                    let name = format!("{:>2}. {}", i + 1, entry.name);
+                    let score = entry.score.to_string();
+                    let level = format!("Lv {}", entry.level);
+                    let date = if entry.date == 0 { "-".to_string() } else { format_timestamp(entry.date) };
+                    let cells = [
+                        Cell::Label(&name, WHITE),
+                        Cell::Label(&score, WHITE),
+                        Cell::Label(&level, WHITE),
+                        Cell::Label(&date, GRAY),
+                    ];
+                    ui.grid_row(&cells, &SCORE_COLUMNS);
+                }
+                ui.space(10.0);
+                // Przełączanie między tabelami z wynikami: poziomami trudności albo dniami wyzwania
+                let mut keys = high_scores.keys(scores_key.mode);
+                if !keys.contains(&scores_key) {
+                    keys.push(scores_key);
+                    if scores_key.mode == GameMode::Normal {
+                        keys.sort_by_key(|k| k.difficulty);
                     }
                 }
-                // Postęp levelu
-                if score > (level as i32 * 100) {
-                    level += 1;
-                }
-                // Usuwanie martwych obiektów
-                for &i in bullets_to_kill.iter().rev() {
-                    bullets[i].alive = false;
+                let index = keys.iter().position(|&k| k == scores_key).unwrap_or(0);
+                let used = ui.row(&[Cell::Button("<"), Cell::Button(">"), Cell::Button("Mode"), Cell::Button("Back")]);
+                if used == Some(0) || nav_pressed(&gamepad, Nav::Left) {
+                    scores_key = keys[(index + keys.len() - 1) % keys.len()];
                 }
-                for &i in enemy_bullets_to_kill.iter().rev() {
-                    enemy_bullets[i].alive = false;
-                }
-                for &i in enemies_to_kill.iter().rev() {
-                    enemies[i].alive = false;
-                }
-                for &i in power_ups_to_kill.iter().rev() {
-                    power_ups[i].alive = false;
-                }
-                bullets.retain(|b| b.alive);
-                enemy_bullets.retain(|b| b.alive);
-                enemies.retain(|e| e.alive);
-                power_ups.retain(|p| p.alive);
-                // Rysowanie
-                draw_rectangle(player_pos.x, player_pos.y, 20.0, 20.0, GREEN); // Gracz
-                for bullet in bullets.iter() {
-                    if bullet.alive {
-                        draw_circle(bullet.pos.x, bullet.pos.y, 5.0, if bullet.bullet_type == 1 { ORANGE } else { YELLOW });
-                    }
-                }
-                for bullet in enemy_bullets.iter() {
-                    if bullet.alive {
-                        draw_circle(bullet.pos.x, bullet.pos.y, 5.0, RED);
-                    }
+                if used == Some(1) || nav_pressed(&gamepad, Nav::Right) {
+                    scores_key = keys[(index + 1) % keys.len()];
                 }
-                for enemy in enemies.iter() {
-                    if enemy.alive {
-                        let color = match enemy.enemy_type {
-                            1 => BLUE, // Fast
-                            2 => PURPLE, // Tank
-                            3 => ORANGE, // Shooter
-                            _ => RED, // Normal
-                        };
-                        draw_rectangle(enemy.pos.x, enemy.pos.y, 20.0, 20.0, color);
-                    }
+                if used == Some(2) || is_key_pressed(KeyCode::Tab) {
+                    let mode = match scores_key.mode {
+                        GameMode::Normal => GameMode::Daily,
+                        GameMode::Daily => GameMode::Normal,
+                    };
+                    scores_key = default_scores_key(mode, settings.difficulty);
                 }
-                for power_up in power_ups.iter() {
-                    if power_up.alive {
-                        let color = match power_up.power_type {
-                            0 => GREEN, // Health
-                            1 => GOLD, // Multi shot
-                            _ => WHITE,
-                        };
-                        draw_circle(power_up.pos.x, power_up.pos.y, 10.0, color);
-                    }
+                if used == Some(3) || nav_pressed(&gamepad, Nav::Back) {
+                    game_state = GameState::Menu;
                 }
-                draw_text(&format!("Score: {}", score), 10.0, 20.0, 20.0, WHITE);
-                draw_text(&format!("Health: {}", player_health), 10.0, 40.0, 20.0, WHITE);
-                draw_text(&format!("Level: {}", level), 10.0, 60.0, 20.0, WHITE);
-                // Powrót do menu
-                if is_key_pressed(KeyCode::Escape) {
-                    if score > settings.high_score {
-                        settings.high_score = score;
-                        save_settings(&settings);
+            }
+            GameState::Replays => {
+                // Lista nagrań
+                let mut ui = Ui::new(&mut screen, &mut menu, &ui_input, 60.0);
+                ui.title("Replays");
+                if replays.is_empty() {
+                    ui.label("No replays found!", WHITE);
+                }
+                let items: Vec<ListItem> = replays
+                    .iter()
+                    .map(|(name, replay)| ListItem {
+                        title: name.clone(),
+                        detail: if !replay.is_playable() {
+                            format!("Score: {}, Seed: {} (other definitions)", replay.score, replay.seed)
+                        } else if replay.is_current() {
+                            format!("Score: {}, Seed: {}", replay.score, replay.seed)
+                        } else {
+                            format!("Score: {}, Seed: {} (older version)", replay.score, replay.seed)
+                        },
+                        color: if !replay.is_playable() {
+                            DARKGRAY
+                        } else if replay.is_current() {
+                            WHITE
+                        } else {
+                            GRAY
+                        },
+                    })
+                    .collect();
+                let chosen = ui.list(&items, &mut replay_list, 8, LIST_WIDTH);
+                let used = ui.row(&[Cell::Button("Play"), Cell::Button("Back")]);
+                if chosen || used == Some(0) {
+                    // Nagrań z innych definicji nie odtwarzamy - przebieg byłby inny niż nagrany
+                    if let Some((_, replay)) = replays.get(replay_list.selected).filter(|(_, replay)| replay.is_playable()) {
+                        playback = Some(Playback::new(replay.clone()));
+                        game_state = GameState::Replay;
                     }
-                    save_game(score, player_pos, settings.difficulty, &enemies, &bullets, &enemy_bullets, &power_ups, player_health, level, shot_level);
+                } else if used == Some(1) || nav_pressed(&gamepad, Nav::Back) {
                     game_state = GameState::Menu;
                 }
             }
+            GameState::Replay => {
+                // Odtwarzanie nagrania
+                let Some(current) = playback.as_mut() else {
+                    game_state = GameState::Replays;
+                    continue;
+                };
+                current.advance(get_frame_time());
+                audio.play_events(&current.world.take_events());
+                draw_world(&canvas, &current.world, current.clock.alpha());
+                draw_text("REPLAY", PLAYFIELD.x - 100.0, 20.0, 20.0, RED);
+                if nav_pressed(&gamepad, Nav::Back) || current.finished() {
+                    playback = None;
+                    game_state = GameState::Replays;
+                }
+            }
             GameState::Settings => {
                 // Ustawienia
-                draw_text("Settings", screen_width() / 2.0 - 50.0, 100.0, 40.0, WHITE);
-                draw_text(
-                    &format!("Difficulty: {:.1}", settings.difficulty),
-                          screen_width() / 2.0 - 50.0,
-                          200.0,
-                          20.0,
-                          WHITE,
-                );
-                if draw_button("+", screen_width() / 2.0 + 50.0, 200.0) {
-                    settings.difficulty += 0.1;
-                }
-                if draw_button("-", screen_width() / 2.0 - 70.0, 200.0) {
-                    settings.difficulty -= 0.1;
-                    settings.difficulty = settings.difficulty.max(0.5);
-                }
-                draw_text(
-                    &format!("Sound Volume: {:.1}", settings.sound_volume),
-                          screen_width() / 2.0 - 50.0,
-                          250.0,
-                          20.0,
-                          WHITE,
-                );
-                if draw_button("+", screen_width() / 2.0 + 50.0, 250.0) {
-                    settings.sound_volume += 0.1;
-                    settings.sound_volume = settings.sound_volume.min(1.0);
+                let mut ui = Ui::new(&mut screen, &mut menu, &ui_input, 60.0);
+                ui.title("Settings");
+                ui.space(10.0);
+                ui.slider("Difficulty", &mut settings.difficulty, 0.5, 5.0, 0.1);
+                // Kanały głośności; zmiana efektów od razu daje próbkę dźwięku
+                let master_changed = ui.slider("Master", &mut settings.master_volume, 0.0, 1.0, 0.1);
+                ui.slider("Music", &mut settings.music_volume, 0.0, 1.0, 0.1);
+                let effects_changed = ui.slider("Effects", &mut settings.sfx_volume, 0.0, 1.0, 0.1);
+                ui.toggle("Mute", &mut settings.muted);
+                // Okno: rozmiar i pełny ekran działają od razu, vsync po ponownym uruchomieniu
+                let display = settings.display;
+                if ui.button(&format!("Window: {}x{}", display.width, display.height)) {
+                    settings.display.next_size();
+                }
+                ui.toggle("Fullscreen", &mut settings.display.fullscreen);
+                ui.toggle("VSync (restart)", &mut settings.display.vsync);
+                let cap = display.fps_cap.map_or("Off".to_string(), |fps| fps.to_string());
+                if ui.button(&format!("FPS Cap: {}", cap)) {
+                    settings.display.next_fps_cap();
+                }
+                display::apply(&display, &settings.display);
+                if ui.button("Controls") {
+                    rebinding = None;
+                    controls_message.clear();
+                    game_state = GameState::Controls;
+                }
+                if master_changed || effects_changed {
+                    audio.configure(&settings);
+                    audio.play(Sfx::PowerUp);
+                }
+                if ui.button("Back") {
+                    save_settings(&settings);
+                    game_state = settings_return;
                 }
-                if draw_button("-", screen_width() / 2.0 - 70.0, 250.0) {
-                    settings.sound_volume -= 0.1;
-                    settings.sound_volume = settings.sound_volume.max(0.0);
+            }
+            GameState::Controls => {
+                // Przypisanie klawiszy: kliknięcie pola czeka na następny wciśnięty klawisz
+                let mut ui = Ui::new(&mut screen, &mut menu, &ui_input, 40.0);
+                ui.title("Controls");
+                ui.space(10.0);
+                // Klawisz, który rozpoczął przechwytywanie, nie może od razu zostać przypisany
+                let capturing = rebinding;
+                for action in Action::ALL {
+                    let labels = settings.bindings.keys(action).map(|key| key.map_or("-".to_string(), Key::name));
+                    let label = |slot: usize| if capturing == Some((action, slot)) { "..." } else { labels[slot].as_str() };
+                    let cells = [Cell::Label(action.label(), WHITE), Cell::Button(label(0)), Cell::Button(label(1))];
+                    if let (Some(cell), None) = (ui.grid_row(&cells, &[160.0, 110.0, 110.0]), rebinding) {
+                        if cell > 0 {
+                            rebinding = Some((action, cell - 1));
+                            controls_message.clear();
+                        }
+                    }
                 }
-                if draw_button("Back", screen_width() / 2.0 - 50.0, 300.0) {
-                    save_settings(&settings);
-                    game_state = GameState::Menu;
+                ui.label(&controls_message, RED);
+                if let Some((action, slot)) = capturing {
+                    ui.label_sized("Press a key (Escape: cancel, Backspace: clear)", 16.0, GRAY);
+                    if is_key_pressed(KeyCode::Backspace) {
+                        settings.bindings.bind(action, slot, None).unwrap_or(());
+                        rebinding = None;
+                    } else if let Some(key) = Key::just_pressed() {
+                        if key.0 != KeyCode::Escape {
+                            if let Err(other) = settings.bindings.bind(action, slot, Some(key)) {
+                                controls_message = format!("{} is already used by {}", key.name(), other.label());
+                            }
+                        }
+                        rebinding = None;
+                    }
+                } else if rebinding.is_none() {
+                    let used = ui.row(&[Cell::Button("Defaults"), Cell::Button("Back")]);
+                    if used == Some(0) {
+                        settings.bindings = Default::default();
+                        controls_message.clear();
+                    }
+                    if used == Some(1) || nav_pressed(&gamepad, Nav::Back) {
+                        game_state = GameState::Settings;
+                    }
                 }
             }
             GameState::LoadGame => {
-                // Wczytywanie gry
-                draw_text("Load Game", screen_width() / 2.0 - 50.0, 100.0, 40.0, WHITE);
-                if let Some(save) = load_game() {
-                    draw_text(
-                        &format!("Score: {}, Health: {}, Level: {}, Difficulty: {:.1}", save.score, save.player_health, save.level, save.difficulty),
-                              screen_width() / 2.0 - 100.0,
-                              200.0,
-                              20.0,
-                              WHITE,
-                    );
-                    if draw_button("Load", screen_width() / 2.0 - 50.0, 250.0) {
-                        score = save.score;
-                        player_pos = vec2(save.player_pos.x, save.player_pos.y);
-                        settings.difficulty = save.difficulty;
-                        player_health = save.player_health;
-                        level = save.level;
-                        shot_level = save.shot_level;
-                        enemies = save.enemies.into_iter().map(|se| Enemy {
-                            pos: vec2(se.pos.x, se.pos.y),
-                                                               speed: se.speed,
-                                                               alive: se.alive,
-                                                               enemy_type: se.enemy_type,
-                                                               health: se.health,
-                                                               last_shot: se.last_shot,
-                        }).collect();
-                        bullets = save.bullets.into_iter().map(|sb| Bullet {
-                            pos: vec2(sb.pos.x, sb.pos.y),
-                                                               vel: vec2(sb.vel.x, sb.vel.y),
-                                                               alive: sb.alive,
-                                                               bullet_type: sb.bullet_type,
-                        }).collect();
-                        enemy_bullets = save.enemy_bullets.into_iter().map(|sb| Bullet {
-                            pos: vec2(sb.pos.x, sb.pos.y),
-                                                                           vel: vec2(sb.vel.x, sb.vel.y),
-                                                                           alive: sb.alive,
-                                                                           bullet_type: sb.bullet_type,
-                        }).collect();
-                        power_ups = save.power_ups.into_iter().map(|sp| PowerUp {
-                            pos: vec2(sp.pos.x, sp.pos.y),
-                                                                   speed: sp.speed,
-                                                                   alive: sp.alive,
-                                                                   power_type: sp.power_type,
-                        }).collect();
-                        game_state = GameState::Game;
+                // Przeglądarka slotów zapisu: fokus na liście wybiera slot, Enter go wczytuje
+                let mut ui = Ui::new(&mut screen, &mut menu, &ui_input, 60.0);
+                ui.title("Load Game");
+                let items: Vec<ListItem> = slots.iter().enumerate().map(|(i, entry)| slot_item(i, entry)).collect();
+                let previous = slot_list.selected;
+                let row_activated = ui.list(&items, &mut slot_list, SLOT_COUNT, LIST_WIDTH);
+                if !slots_message.is_empty() {
+                    ui.label_sized(&slots_message, 16.0, GOLD);
+                }
+                let selected_slot = slot_list.selected;
+                if selected_slot != previous {
+                    confirm_delete = false;
+                    renaming = None;
+                }
+                let selected_save = slots.get(selected_slot).and_then(|s| s.as_ref().ok());
+                let resumable = selected_save.is_some_and(|s| !s.finished);
+                let can_overwrite = session.as_ref().is_some_and(Session::is_active);
+                let occupied = !matches!(slots.get(selected_slot), None | Some(Err(LoadError::NotFound)));
+                if let Some(buffer) = renaming.as_mut() {
+                    // Wpisywanie nazwy: Enter zatwierdza, Escape anuluje
+                    let event = ui.text_input("Name", buffer, 20);
+                    ui.label_sized("Enter: confirm, Escape: cancel", 16.0, GRAY);
+                    match event {
+                        TextEvent::Submitted => {
+                            if !buffer.trim().is_empty() {
+                                rename_slot(selected_slot, buffer.trim()).unwrap_or(());
+                                if let Some(current) = session.as_mut().filter(|s| s.slot == Some(selected_slot)) {
+                                    current.slot_name = buffer.trim().to_string();
+                                }
+                            }
+                            renaming = None;
+                            slots = list_slots();
+                        }
+                        TextEvent::Cancelled => renaming = None,
+                        TextEvent::Editing => {}
                     }
                 } else {
-                    draw_text("No save found!", screen_width() / 2.0 - 50.0, 200.0, 20.0, WHITE);
-                }
-                if draw_button("Back", screen_width() / 2.0 - 50.0, 300.0) {
-                    game_state = GameState::Menu;
+                    let used = ui.row(&[
+                        Cell::Button("Load"),
+                        Cell::Button("Overwrite"),
+                        Cell::Button("Rename"),
+                        Cell::Button(if confirm_delete { "Confirm?" } else { "Delete" }),
+                        Cell::Button("Back"),
+                    ]);
+                    ui.label_sized("Up/Down: move, Enter: choose", 16.0, GRAY);
+                    ui.label_sized("O: overwrite, R: rename, Del: delete", 16.0, GRAY);
+                    let load = used == Some(0) || row_activated;
+                    let overwrite = used == Some(1) || is_key_pressed(KeyCode::O);
+                    let rename = used == Some(2) || is_key_pressed(KeyCode::R);
+                    let delete = used == Some(3) || is_key_pressed(KeyCode::Delete);
+                    let back = used == Some(4) || nav_pressed(&gamepad, Nav::Back);
+                    if load && resumable {
+                        if let Ok(save) = load_game(selected_slot) {
+                            settings.difficulty = save.difficulty;
+                            session = Some(Session::from_save(save, selected_slot));
+                            game_state = GameState::Game;
+                        }
+                    } else if overwrite && can_overwrite {
+                        // Bieżąca gra trafia do wybranego slotu (nazwa slotu zostaje)
+                        if let Some(current) = session.as_mut() {
+                            current.slot = Some(selected_slot);
+                            current.slot_name = selected_save.map(|s| s.name.clone()).unwrap_or_else(|| default_slot_name(selected_slot));
+                            save_game(selected_slot, &current.slot_name, current.mode, &current.world);
+                        }
+                        slots = list_slots();
+                        slots_message.clear();
+                    } else if rename && selected_save.is_some() {
+                        renaming = selected_save.map(|s| s.name.clone());
+                    } else if delete && occupied {
+                        if confirm_delete {
+                            delete_slot(selected_slot);
+                            confirm_delete = false;
+                            slots = list_slots();
+                        } else {
+                            confirm_delete = true;
+                        }
+                    } else if back {
+                        game_state = GameState::Menu;
+                    }
                 }
             }
         }
+        // Ruch fokusu, którego nie zużył żaden widżet
+        menu.end();
+        canvas.present();
+        limiter.wait(settings.display.fps_cap);
         next_frame().await;
     }
 }
-// Funkcja do rysowania przycisku
-fn draw_button(text: &str, x: f32, y: f32) -> bool {
-    let text_width = measure_text(text, None, 20, 1.0).width;
-    let rect_width = text_width + 20.0;
-    let rect_height = 30.0;
-    let mouse_pos = mouse_position();
-    let is_hovered = mouse_pos.0 > x && mouse_pos.0 < x + rect_width && mouse_pos.1 > y && mouse_pos.1 < y + rect_height;
-    draw_rectangle(x, y, rect_width, rect_height, if is_hovered { GRAY } else { DARKGRAY });
-    draw_text(text, x + 10.0, y + 20.0, 20.0, WHITE);
-    is_hovered && is_mouse_button_pressed(MouseButton::Left)
+// Tabela pokazywana na start: Normal na bieżącym poziomie trudności albo dzisiejsze wyzwanie
+fn default_scores_key(mode: GameMode, difficulty: f32) -> TableKey {
+    match mode {
+        GameMode::Normal => TableKey::new(mode, difficulty, 0),
+        GameMode::Daily => TableKey::new(mode, DAILY_DIFFICULTY, daily_seed(today(), defs::current().fingerprint())),
+    }
+}
+// Nagłówek tabeli wyników; dzień wyzwania odczytany z najstarszego wpisu (pusta tabela to dzisiejsza)
+fn scores_title(key: TableKey, table: &[ScoreEntry]) -> String {
+    match key.mode {
+        GameMode::Normal => format!("{} - Difficulty: {:.1}", key.mode.label(), key.difficulty as f32 / 10.0),
+        GameMode::Daily => {
+            let (year, month, day) = table.iter().map(|e| e.date).min().map_or_else(today, |date| civil_from_days((date / 86_400) as i64));
+            format!("{} - {:04}-{:02}-{:02}", key.mode.label(), year, month, day)
+        }
+    }
 }
-// Funkcja zapisu gry
-fn save_game(score: i32, player_pos: Vec2, difficulty: f32, enemies: &Vec<Enemy>, bullets: &Vec<Bullet>, enemy_bullets: &Vec<Bullet>, power_ups: &Vec<PowerUp>, player_health: i32, level: u32, shot_level: u32) {
-    let save = GameSave {
-        score,
-        player_pos: SerVec2 { x: player_pos.x, y: player_pos.y },
-        difficulty,
-        enemies: enemies.iter().map(|e| SerEnemy {
-            pos: SerVec2 { x: e.pos.x, y: e.pos.y },
-            speed: e.speed,
-            alive: e.alive,
-            enemy_type: e.enemy_type,
-            health: e.health,
-            last_shot: e.last_shot,
-        }).collect(),
-        bullets: bullets.iter().map(|b| SerBullet {
-            pos: SerVec2 { x: b.pos.x, y: b.pos.y },
-            vel: SerVec2 { x: b.vel.x, y: b.vel.y },
-            alive: b.alive,
-            bullet_type: b.bullet_type,
-        }).collect(),
-        enemy_bullets: enemy_bullets.iter().map(|b| SerBullet {
-            pos: SerVec2 { x: b.pos.x, y: b.pos.y },
-            vel: SerVec2 { x: b.vel.x, y: b.vel.y },
-            alive: b.alive,
-            bullet_type: b.bullet_type,
-        }).collect(),
-        power_ups: power_ups.iter().map(|p| SerPowerUp {
-            pos: SerVec2 { x: p.pos.x, y: p.pos.y },
-            speed: p.speed,
-            alive: p.alive,
-            power_type: p.power_type,
-        }).collect(),
-        player_health,
-        level,
-        shot_level,
+// Pozycja przeglądarki slotów
+fn slot_item(i: usize, entry: &Result<GameSave, LoadError>) -> ListItem {
+    let (title, detail, color) = match entry {
+        Ok(save) => (
+            format!("{}. {}{} - {}", i + 1, save.name, if save.finished { " (finished)" } else { "" }, format_timestamp(save.saved_at)),
+            format!(
+                "Score: {}, Level: {}, Difficulty: {:.1}, Time: {}",
+                save.score,
+                save.level,
+                save.difficulty,
+                format_duration(save.play_time)
+            ),
+            WHITE,
+        ),
+        Err(LoadError::NotFound) => (format!("{}. <empty>", i + 1), String::new(), GRAY),
+        Err(e) => (format!("{}. {}", i + 1, default_slot_name(i)), e.to_string(), RED),
     };
-    let serialized = serde_json::to_string(&save).unwrap();
-    fs::write("save.json", serialized).unwrap_or(());
+    ListItem { title, detail, color }
 }
-// Funkcja wczytywania gry
-fn load_game() -> Option<GameSave> {
-    if let Ok(data) = fs::read_to_string("save.json") {
-        serde_json::from_str(&data).ok()
-    } else {
-        None
+// Wejście symulacji z klawiatury i pada naraz
+fn read_input(bindings: &Bindings, gamepad: &Gamepad) -> Input {
+    let keys = bindings.read_input();
+    let pad = gamepad.read_input();
+    Input {
+        left: keys.left || pad.left,
+        right: keys.right || pad.right,
+        up: keys.up || pad.up,
+        down: keys.down || pad.down,
+        fire: keys.fire || pad.fire,
     }
 }
-// Funkcja zapisu ustawień
-fn save_settings(settings: &Settings) {
-    let serialized = serde_json::to_string(settings).unwrap();
-    fs::write("settings.json", serialized).unwrap_or(());
+// Sterowanie fokusem menu: strzałki/D-pad, Enter/Spacja/A aktywuje
+fn read_menu_input(gamepad: &Gamepad) -> MenuInput {
+    MenuInput {
+        up: nav_pressed(gamepad, Nav::Up),
+        down: nav_pressed(gamepad, Nav::Down),
+        left: nav_pressed(gamepad, Nav::Left),
+        right: nav_pressed(gamepad, Nav::Right),
+        activate: nav_pressed(gamepad, Nav::Confirm) || is_key_pressed(KeyCode::Space),
+    }
 }
-// Funkcja wczytywania ustawień
-fn load_settings() -> Settings {
-    if let Ok(data) = fs::read_to_string("settings.json") {
-        serde_json::from_str(&data).unwrap_or(Settings {
-            difficulty: 1.0,
-            sound_volume: 0.5,
-            high_score: 0,
-        })
-    } else {
-        Settings {
-            difficulty: 1.0,
-            sound_volume: 0.5,
-            high_score: 0,
-        }
+// Nawigacja po menu: klawiatura albo D-pad
+fn nav_pressed(gamepad: &Gamepad, nav: Nav) -> bool {
+    let key = match nav {
+        Nav::Up => KeyCode::Up,
+        Nav::Down => KeyCode::Down,
+        Nav::Left => KeyCode::Left,
+        Nav::Right => KeyCode::Right,
+        Nav::Confirm => KeyCode::Enter,
+        Nav::Back => KeyCode::Escape,
+    };
+    is_key_pressed(key) || gamepad.nav_pressed(nav)
+}
+// Rysowanie stanu symulacji; `alpha` to ułamek kroku do interpolacji pozycji
+fn draw_world(canvas: &Canvas, world: &World, alpha: f32) {
+    canvas.set_arena(world.arena);
+    let player_pos = world.prev_player_pos.lerp(world.player_pos, alpha);
+    draw_rectangle(player_pos.x, player_pos.y, PLAYER_SIZE.x, PLAYER_SIZE.y, GREEN); // Gracz
+    for bullet in world.bullets.values() {
+        let pos = bullet.prev_pos.lerp(bullet.pos, alpha);
+        let def = world.defs.bullet(bullet.kind);
+        draw_circle(pos.x, pos.y, def.radius, def.color);
+    }
+    for bullet in world.enemy_bullets.values() {
+        let pos = bullet.prev_pos.lerp(bullet.pos, alpha);
+        let def = world.defs.bullet(bullet.kind);
+        draw_circle(pos.x, pos.y, def.radius, def.color);
+    }
+    for enemy in world.enemies.values() {
+        let def = world.defs.enemy(enemy.kind);
+        let pos = enemy.prev_pos.lerp(enemy.pos, alpha);
+        draw_rectangle(pos.x, pos.y, def.width, def.height, def.color);
+    }
+    for power_up in world.power_ups.values() {
+        let def = world.defs.power_up(power_up.kind);
+        let pos = power_up.prev_pos.lerp(power_up.pos, alpha);
+        draw_circle(pos.x, pos.y, def.radius, def.color);
     }
+    draw_text(format!("Score: {}", world.score), 10.0, 20.0, 20.0, WHITE);
+    draw_text(format!("Health: {}", world.player_health), 10.0, 40.0, 20.0, WHITE);
+    draw_text(format!("Level: {}", world.level), 10.0, 60.0, 20.0, WHITE);
+    canvas.set_logical();
 }
-
@@ -0,0 +1,121 @@
+// Fokus w menu: widżety rejestrują się co klatkę w kolejności rysowania,
+// a klawiatura/pad przesuwa fokus i aktywuje wskazany widżet
+#[derive(Clone, Copy, Default)]
+pub struct MenuInput {
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+    pub activate: bool,
+}
+pub struct MenuFocus {
+    focused: usize,
+    count: usize, // widżety zarejestrowane w bieżącej klatce
+    input: MenuInput,
+}
+impl MenuFocus {
+    pub fn new() -> MenuFocus {
+        MenuFocus { focused: 0, count: 0, input: MenuInput::default() }
+    }
+    // Nowy ekran - fokus na wskazanym widżecie
+    pub fn reset(&mut self, focused: usize) {
+        self.focused = focused;
+        self.count = 0;
+    }
+    // Początek klatki
+    pub fn begin(&mut self, input: MenuInput) {
+        self.count = 0;
+        self.input = input;
+    }
+    // Koniec klatki: ruch góra/dół, którego nie zużył widżet, przesuwa fokus
+    pub fn end(&mut self) {
+        let count = self.count;
+        if count == 0 {
+            return;
+        }
+        self.focused = self.focused.min(count - 1);
+        if self.input.up {
+            self.focused = (self.focused + count - 1) % count;
+        }
+        if self.input.down {
+            self.focused = (self.focused + 1) % count;
+        }
+    }
+    // Rejestruje widżet i zwraca jego numer
+    pub fn widget(&mut self) -> usize {
+        self.count += 1;
+        self.count - 1
+    }
+    pub fn focus(&mut self, id: usize) {
+        self.focused = id;
+    }
+    pub fn is_focused(&self, id: usize) -> bool {
+        self.focused == id
+    }
+    pub fn activated(&self, id: usize) -> bool {
+        self.is_focused(id) && self.input.activate
+    }
+    // Zmiana wartości widżetu lewo/prawo: -1, 0 albo 1
+    pub fn adjust(&self, id: usize) -> i32 {
+        if !self.is_focused(id) {
+            0
+        } else {
+            self.input.right as i32 - self.input.left as i32
+        }
+    }
+    // Ruch góra/dół dla widżetu z fokusem (np. listy): -1, 0 albo 1
+    pub fn vertical(&self, id: usize) -> i32 {
+        if !self.is_focused(id) {
+            0
+        } else {
+            self.input.down as i32 - self.input.up as i32
+        }
+    }
+    // Widżet wykorzystał ruch góra/dół - fokus zostaje na miejscu
+    pub fn consume_vertical(&mut self) {
+        self.input.up = false;
+        self.input.down = false;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(menu: &mut MenuFocus, input: MenuInput, widgets: usize) -> Vec<usize> {
+        menu.begin(input);
+        let ids: Vec<usize> = (0..widgets).map(|_| menu.widget()).collect();
+        let focused = ids.into_iter().filter(|&id| menu.is_focused(id)).collect();
+        menu.end();
+        focused
+    }
+
+    #[test]
+    fn focus_moves_and_wraps() {
+        let mut menu = MenuFocus::new();
+        let down = MenuInput { down: true, ..MenuInput::default() };
+        let up = MenuInput { up: true, ..MenuInput::default() };
+        assert_eq!(frame(&mut menu, down, 3), vec![0]);
+        assert_eq!(frame(&mut menu, down, 3), vec![1]);
+        assert_eq!(frame(&mut menu, down, 3), vec![2]);
+        assert_eq!(frame(&mut menu, up, 3), vec![0]);
+        assert_eq!(frame(&mut menu, MenuInput::default(), 3), vec![2]);
+        // Po zniknięciu widżetu fokus przechodzi na ostatni istniejący
+        frame(&mut menu, MenuInput::default(), 2);
+        assert_eq!(frame(&mut menu, MenuInput::default(), 2), vec![1]);
+    }
+
+    #[test]
+    fn only_focused_widget_reacts() {
+        let mut menu = MenuFocus::new();
+        menu.reset(1);
+        menu.begin(MenuInput { activate: true, left: true, down: true, ..MenuInput::default() });
+        let (a, b) = (menu.widget(), menu.widget());
+        assert!(!menu.activated(a) && menu.activated(b));
+        assert_eq!((menu.adjust(a), menu.adjust(b)), (0, -1));
+        assert_eq!((menu.vertical(a), menu.vertical(b)), (0, 1));
+        // Zużyty ruch nie przesuwa fokusu
+        menu.consume_vertical();
+        menu.end();
+        assert!(menu.is_focused(b));
+    }
+}
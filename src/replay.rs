@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use crate::date::now_secs;
+use crate::defs::{self, Definitions};
+use crate::save::SerVec2;
+use crate::timestep::{FixedStep, TICK};
+use crate::world::{Input, World};
+// Bity wejścia zapisywane dla każdego kroku
+const LEFT: u8 = 1;
+const RIGHT: u8 = 2;
+const UP: u8 = 4;
+const DOWN: u8 = 8;
+const SPACE: u8 = 16;
+const ESCAPE: u8 = 32;
+// Katalog z nagraniami
+const REPLAY_DIR: &str = "replays";
+// Wersja symulacji, w której powstaje nagranie - do podniesienia przy każdej zmianie przebiegu gry.
+// Nagrania z innej wersji mogą się odtwarzać inaczej niż zostały nagrane
+pub const REPLAY_VERSION: u32 = 3;
+// Nagranie rozgrywki: parametry startowe + wejście z każdego kroku symulacji
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Replay {
+    #[serde(default = "first_version")]
+    pub version: u32, // nagrania bez tego pola to wersja 1
+    #[serde(default = "built_in_hash")]
+    pub defs_hash: u64, // nagrania bez tego pola powstały na wbudowanych definicjach
+    pub seed: u64,
+    pub difficulty: f32,
+    pub arena: SerVec2,
+    pub score: i32,
+    pub inputs: Vec<u8>,
+}
+impl Replay {
+    // Rozpoczęcie nagrywania świeżo utworzonej gry
+    pub fn start(world: &World) -> Replay {
+        Replay {
+            version: REPLAY_VERSION,
+            defs_hash: world.defs.fingerprint(),
+            seed: world.seed,
+            difficulty: world.difficulty,
+            arena: world.arena.into(),
+            score: 0,
+            inputs: vec![],
+        }
+    }
+    pub fn record(&mut self, input: &Input) {
+        self.inputs.push(encode(input));
+    }
+    // Koniec nagrania; Escape jest dopisywany do ostatniego kroku
+    pub fn finish(&mut self, score: i32, escape: bool) {
+        self.score = score;
+        if escape {
+            match self.inputs.last_mut() {
+                Some(last) => *last |= ESCAPE,
+                None => self.inputs.push(ESCAPE),
+            }
+        }
+    }
+    // Czy nagranie powstało w tej wersji symulacji
+    pub fn is_current(&self) -> bool {
+        self.version == REPLAY_VERSION
+    }
+    // Czy nagranie powstało na wczytanych definicjach - na innych nie da się go odtworzyć
+    pub fn is_playable(&self) -> bool {
+        self.defs_hash == defs::current().fingerprint()
+    }
+    // Świat w stanie początkowym nagrania
+    pub fn world(&self) -> World {
+        World::new(self.arena.into(), self.difficulty, self.seed)
+    }
+}
+fn first_version() -> u32 {
+    1
+}
+fn built_in_hash() -> u64 {
+    Definitions::default().fingerprint()
+}
+fn encode(input: &Input) -> u8 {
+    let mut bits = 0;
+    for (down, bit) in [(input.left, LEFT), (input.right, RIGHT), (input.up, UP), (input.down, DOWN), (input.fire, SPACE)] {
+        if down {
+            bits |= bit;
+        }
+    }
+    bits
+}
+fn decode(bits: u8) -> Input {
+    Input {
+        left: bits & LEFT != 0,
+        right: bits & RIGHT != 0,
+        up: bits & UP != 0,
+        down: bits & DOWN != 0,
+        fire: bits & SPACE != 0,
+    }
+}
+// Odtwarzanie nagrania krok po kroku na osobnym świecie
+pub struct Playback {
+    pub replay: Replay,
+    pub world: World,
+    pub clock: FixedStep,
+    cursor: usize,
+    escaped: bool,
+}
+impl Playback {
+    pub fn new(replay: Replay) -> Playback {
+        let world = replay.world();
+        let clock = FixedStep::new();
+        Playback { replay, world, clock, cursor: 0, escaped: false }
+    }
+    // Wykonuje tyle kroków nagrania, ile mieści się w czasie klatki
+    pub fn advance(&mut self, frame_time: f32) {
+        self.clock.accumulate(frame_time);
+        while self.clock.tick() {
+            match self.next_input() {
+                Some(input) => self.world.step(&input, TICK),
+                None => break,
+            }
+        }
+    }
+    // Wejście dla następnego kroku albo None, gdy nagranie się skończyło
+    fn next_input(&mut self) -> Option<Input> {
+        if self.escaped {
+            return None;
+        }
+        let bits = *self.replay.inputs.get(self.cursor)?;
+        self.cursor += 1;
+        self.escaped = bits & ESCAPE != 0;
+        Some(decode(bits))
+    }
+    pub fn finished(&self) -> bool {
+        self.escaped || self.cursor >= self.replay.inputs.len()
+    }
+}
+// Funkcja zapisu nagrania
+pub fn save_replay(replay: &Replay) {
+    let name = replay_file_name(now_secs(), replay.seed, |name| PathBuf::from(REPLAY_DIR).join(name).exists());
+    let path = PathBuf::from(REPLAY_DIR).join(name);
+    let serialized = serde_json::to_string(replay).unwrap();
+    fs::create_dir_all(REPLAY_DIR).and_then(|_| fs::write(path, serialized)).unwrap_or(());
+}
+// Nazwa pliku nagrania: chwila zakończenia i seed, a gdy taki plik już jest - kolejny numer
+fn replay_file_name(secs: u64, seed: u64, taken: impl Fn(&str) -> bool) -> String {
+    let base = format!("run-{}-{}", secs, seed);
+    let mut name = format!("{}.json", base);
+    let mut copy = 2;
+    while taken(&name) {
+        name = format!("{}-{}.json", base, copy);
+        copy += 1;
+    }
+    name
+}
+// Lista nagrań (nazwa pliku, nagranie), od najnowszego
+pub fn list_replays() -> Vec<(String, Replay)> {
+    let mut replays = vec![];
+    if let Ok(entries) = fs::read_dir(REPLAY_DIR) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if let Ok(data) = fs::read_to_string(entry.path()) {
+                if let Ok(replay) = serde_json::from_str(&data) {
+                    replays.push((name, replay));
+                }
+            }
+        }
+    }
+    replays.sort_by(|a, b| b.0.cmp(&a.0));
+    replays
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macroquad::prelude::vec2;
+
+    #[test]
+    fn input_bits_roundtrip() {
+        let input = Input { left: true, up: true, fire: true, ..Input::default() };
+        let decoded = decode(encode(&input));
+        assert!(decoded.left && decoded.up && decoded.fire);
+        assert!(!decoded.right && !decoded.down);
+    }
+
+    #[test]
+    fn old_replays_are_marked_as_another_version() {
+        let replay: Replay = serde_json::from_str(r#"{"seed":1,"difficulty":1.0,"arena":{"x":800.0,"y":600.0},"score":0,"inputs":[]}"#).unwrap();
+        assert_eq!(replay.version, 1);
+        assert!(!replay.is_current());
+        assert!(Replay::start(&replay.world()).is_current());
+        assert!(replay.is_playable());
+    }
+
+    #[test]
+    fn replays_from_other_definitions_are_not_playable() {
+        let mut replay = Replay::start(&World::new(vec2(640.0, 480.0), 1.0, 3));
+        assert!(replay.is_playable());
+        replay.defs_hash ^= 1;
+        let replay: Replay = serde_json::from_str(&serde_json::to_string(&replay).unwrap()).unwrap();
+        assert!(!replay.is_playable());
+    }
+
+    #[test]
+    fn replays_from_the_same_second_get_their_own_files() {
+        assert_eq!(replay_file_name(100, 7, |_| false), "run-100-7.json");
+        let taken = ["run-100-7.json", "run-100-7-2.json"];
+        assert_eq!(replay_file_name(100, 7, |name| taken.contains(&name)), "run-100-7-3.json");
+    }
+
+    #[test]
+    fn playback_reproduces_run() {
+        let mut world = World::new(vec2(640.0, 480.0), 2.0, 99);
+        let mut replay = Replay::start(&world);
+        for tick in 0..3_000u32 {
+            let input = Input { left: tick % 300 < 150, right: tick % 300 >= 150, fire: true, ..Input::default() };
+            world.step(&input, TICK);
+            replay.record(&input);
+            if world.is_over() {
+                break;
+            }
+        }
+        replay.finish(world.score, true);
+        let replay: Replay = serde_json::from_str(&serde_json::to_string(&replay).unwrap()).unwrap();
+        let mut playback = Playback::new(replay);
+        // Odtwarzanie przy innej liczbie klatek na sekundę niż nagranie
+        while !playback.finished() {
+            playback.advance(1.0 / 37.0);
+        }
+        let copy = &playback.world;
+        assert_eq!(copy.score, world.score);
+        assert_eq!(copy.player_health, world.player_health);
+        assert_eq!(copy.player_pos, world.player_pos);
+        assert_eq!(copy.enemies.len(), world.enemies.len());
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use ::rand::RngCore;
+// Deterministyczny generator (splitmix64) - ten sam seed daje tę samą rozgrywkę,
+// a stan można zapisać razem z grą
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GameRng {
+    state: u64,
+}
+impl GameRng {
+    pub fn new(seed: u64) -> GameRng {
+        GameRng { state: seed }
+    }
+}
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), ::rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+// Losowy seed dla zwykłej gry
+pub fn random_seed() -> u64 {
+    ::rand::thread_rng().next_u64()
+}
+// Seed wyzwania dnia - taki sam dla wszystkich graczy danego dnia grających na tych samych definicjach
+pub fn daily_seed((year, month, day): (i32, u32, u32), defs_hash: u64) -> u64 {
+    let date = year as u64 * 10_000 + month as u64 * 100 + day as u64;
+    GameRng::new(date ^ defs_hash).next_u64()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::rand::Rng;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = GameRng::new(42);
+        let mut b = GameRng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.gen_range(0..1000), b.gen_range(0..1000));
+        }
+        assert_ne!(GameRng::new(1).next_u64(), GameRng::new(2).next_u64());
+    }
+
+    #[test]
+    fn daily_seed_changes_with_date() {
+        assert_eq!(daily_seed((2026, 10, 17), 5), daily_seed((2026, 10, 17), 5));
+        assert_ne!(daily_seed((2026, 10, 17), 5), daily_seed((2026, 10, 18), 5));
+        assert_ne!(daily_seed((2026, 10, 17), 5), daily_seed((2026, 10, 17), 6));
+    }
+}
@@ -0,0 +1,565 @@
+use macroquad::prelude::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use crate::date::now_secs;
+use crate::collision::PLAYER_SIZE;
+use crate::defs::Definitions;
+use crate::controls::Bindings;
+use crate::display::{self, DisplaySettings};
+use crate::highscores::GameMode;
+use crate::kinds::{BulletKind, EnemyKind, KindName, PowerUpKind};
+use crate::rng::GameRng;
+use crate::world::{Bullet, Enemy, PowerUp, World};
+// Serializable Vec2
+#[derive(Serialize, Deserialize, Copy, Clone)]
+pub struct SerVec2 {
+    pub x: f32,
+    pub y: f32,
+}
+// Serializable Enemy
+#[derive(Serialize, Deserialize)]
+pub struct SerEnemy {
+    pub id: u32,
+    pub pos: SerVec2,
+    pub speed: f32,
+    pub alive: bool,
+    pub enemy_type: KindName, // nazwa z definicji; starsze zapisy: numer 0-3
+    pub health: i32,
+    pub last_shot: f64,
+}
+// Serializable Bullet
+#[derive(Serialize, Deserialize)]
+pub struct SerBullet {
+    pub pos: SerVec2,
+    pub vel: SerVec2,
+    pub alive: bool,
+    pub bullet_type: KindName, // starsze zapisy: 0 normal, 1 power-up
+    pub pierce: u32,
+    pub hit: Vec<u32>, // id trafionych wrogów
+}
+// Serializable PowerUp
+#[derive(Serialize, Deserialize)]
+pub struct SerPowerUp {
+    pub pos: SerVec2,
+    pub speed: f32,
+    pub alive: bool,
+    pub power_type: KindName, // starsze zapisy: 0 health, 1 multi shot
+}
+// Aktualna wersja formatu zapisu
+pub const SAVE_VERSION: u32 = 9;
+// Liczba slotów zapisu i ich katalog
+pub const SLOT_COUNT: usize = 5;
+const SAVE_DIR: &str = "saves";
+// Zapis sprzed slotów - przenoszony do pierwszego slotu
+const LEGACY_SAVE: &str = "save.json";
+// Struktura zapisu gry
+#[derive(Serialize, Deserialize)]
+pub struct GameSave {
+    pub version: u32,
+    pub name: String,
+    pub mode: GameMode,
+    pub saved_at: u64, // sekundy od 1970-01-01
+    pub play_time: f64, // sekundy; zarazem zegar gry, względem którego zapisane są liczniki
+    pub last_shot: f64,
+    pub last_power_up: f64,
+    pub finished: bool, // gra zakończona śmiercią - nie da się jej wznowić
+    pub kills: Vec<u32>, // według kolejności wrogów w definicjach
+    pub score: i32,
+    pub arena: SerVec2, // rozmiar planszy, w której jednostkach zapisane są pozycje
+    pub player_pos: SerVec2,
+    pub difficulty: f32,
+    pub enemies: Vec<SerEnemy>,
+    pub bullets: Vec<SerBullet>,
+    pub enemy_bullets: Vec<SerBullet>,
+    pub power_ups: Vec<SerPowerUp>,
+    pub player_health: i32,
+    pub level: u32,
+    pub shot_level: u32,
+    pub seed: u64,
+    pub rng: Option<GameRng>,
+}
+// Struktura ustawień
+#[derive(Serialize, Deserialize)]
+pub struct Settings {
+    pub difficulty: f32,
+    #[serde(alias = "sound_volume")]
+    pub master_volume: f32,
+    #[serde(default = "full_volume")]
+    pub music_volume: f32,
+    #[serde(default = "full_volume")]
+    pub sfx_volume: f32,
+    #[serde(default)]
+    pub muted: bool,
+    #[serde(default)]
+    pub player_name: String, // ostatnio wpisana nazwa do tabeli wyników
+    #[serde(default)]
+    pub bindings: Bindings,
+    #[serde(default, deserialize_with = "display::lenient")]
+    pub display: DisplaySettings,
+}
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            difficulty: 1.0,
+            master_volume: 0.5,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            muted: false,
+            player_name: String::new(),
+            bindings: Bindings::default(),
+            display: DisplaySettings::default(),
+        }
+    }
+}
+fn full_volume() -> f32 {
+    1.0
+}
+impl From<Vec2> for SerVec2 {
+    fn from(v: Vec2) -> SerVec2 {
+        SerVec2 { x: v.x, y: v.y }
+    }
+}
+impl From<SerVec2> for Vec2 {
+    fn from(v: SerVec2) -> Vec2 {
+        vec2(v.x, v.y)
+    }
+}
+fn ser_bullet(b: &Bullet, defs: &Definitions) -> SerBullet {
+    SerBullet {
+        pos: b.pos.into(),
+        vel: b.vel.into(),
+        alive: true,
+        bullet_type: KindName::Name(defs.bullet(b.kind).name.clone()),
+        pierce: b.pierce,
+        hit: b.hit.clone(),
+    }
+}
+impl GameSave {
+    // Zapis stanu symulacji
+    pub fn from_world(world: &World, name: &str, mode: GameMode) -> GameSave {
+        let defs = world.defs;
+        GameSave {
+            version: SAVE_VERSION,
+            name: name.to_string(),
+            mode,
+            saved_at: now_secs(),
+            play_time: world.time,
+            last_shot: world.last_shot,
+            last_power_up: world.last_power_up,
+            finished: world.is_over(),
+            kills: world.kills.clone(),
+            score: world.score,
+            arena: world.arena.into(),
+            player_pos: world.player_pos.into(),
+            difficulty: world.difficulty,
+            enemies: world.enemies.values().map(|e| SerEnemy {
+                id: e.id,
+                pos: e.pos.into(),
+                speed: e.speed,
+                alive: true,
+                enemy_type: KindName::Name(defs.enemy(e.kind).name.clone()),
+                health: e.health,
+                last_shot: e.last_shot,
+            }).collect(),
+            bullets: world.bullets.values().map(|b| ser_bullet(b, defs)).collect(),
+            enemy_bullets: world.enemy_bullets.values().map(|b| ser_bullet(b, defs)).collect(),
+            power_ups: world.power_ups.values().map(|p| SerPowerUp {
+                pos: p.pos.into(),
+                speed: p.speed,
+                alive: true,
+                power_type: KindName::Name(defs.power_up(p.kind).name.clone()),
+            }).collect(),
+            player_health: world.player_health,
+            level: world.level,
+            shot_level: world.shot_level,
+            seed: world.seed,
+            rng: Some(world.rng.clone()),
+        }
+    }
+    // Odtworzenie symulacji z zapisu; pozycje i prędkości z planszy innego rozmiaru są przeskalowane
+    // (wrogowie i power-upy lecą w dół, więc ich prędkość skaluje się wysokością planszy).
+    // Obiekty rodzajów, których nie ma już w definicjach, są pomijane
+    pub fn into_world(self, arena: Vec2) -> World {
+        let mut world = World::new(arena, self.difficulty, self.seed);
+        if let Some(rng) = self.rng {
+            world.rng = rng;
+        }
+        let defs = world.defs;
+        let built_in = Definitions::default();
+        let scale = arena / Vec2::from(self.arena);
+        let at = |v: SerVec2| Vec2::from(v) * scale;
+        let de_bullet = |sb: SerBullet| {
+            let kind = sb.bullet_type.resolve(built_in.bullets.iter().map(|def| def.name.as_str()), defs.bullets.iter().map(|def| def.name.as_str()))?;
+            Some(Bullet {
+                pos: at(sb.pos),
+                prev_pos: at(sb.pos),
+                vel: at(sb.vel),
+                kind: BulletKind(kind),
+                pierce: sb.pierce,
+                hit: sb.hit,
+            })
+        };
+        world.score = self.score;
+        world.player_pos = at(self.player_pos).clamp(Vec2::ZERO, arena - PLAYER_SIZE);
+        world.prev_player_pos = world.player_pos;
+        world.player_health = self.player_health;
+        world.level = self.level;
+        world.shot_level = self.shot_level;
+        world.time = self.play_time;
+        world.last_shot = self.last_shot;
+        world.last_power_up = self.last_power_up;
+        world.kills = self.kills;
+        world.kills.resize(defs.enemies.len(), 0);
+        world.next_enemy_id = self.enemies.iter().map(|se| se.id + 1).max().unwrap_or(0);
+        // Martwe obiekty ze starszych zapisów są pomijane
+        world.enemies = self.enemies.into_iter().filter(|se| se.alive).filter_map(|se| {
+            let kind = se.enemy_type.resolve(built_in.enemies.iter().map(|def| def.name.as_str()), defs.enemies.iter().map(|def| def.name.as_str()))?;
+            Some(Enemy {
+                id: se.id,
+                pos: at(se.pos),
+                prev_pos: at(se.pos),
+                speed: se.speed * scale.y,
+                kind: EnemyKind(kind),
+                health: se.health,
+                last_shot: se.last_shot,
+            })
+        }).collect();
+        world.bullets = self.bullets.into_iter().filter(|sb| sb.alive).filter_map(&de_bullet).collect();
+        world.enemy_bullets = self.enemy_bullets.into_iter().filter(|sb| sb.alive).filter_map(de_bullet).collect();
+        world.power_ups = self.power_ups.into_iter().filter(|sp| sp.alive).filter_map(|sp| {
+            let kind = sp.power_type.resolve(built_in.power_ups.iter().map(|def| def.name.as_str()), defs.power_ups.iter().map(|def| def.name.as_str()))?;
+            Some(PowerUp {
+                pos: at(sp.pos),
+                prev_pos: at(sp.pos),
+                speed: sp.speed * scale.y,
+                kind: PowerUpKind(kind),
+            })
+        }).collect();
+        world
+    }
+}
+fn slot_path(slot: usize) -> PathBuf {
+    PathBuf::from(SAVE_DIR).join(format!("slot-{}.json", slot + 1))
+}
+// Domyślna nazwa slotu
+pub fn default_slot_name(slot: usize) -> String {
+    format!("Slot {}", slot + 1)
+}
+// Funkcja zapisu gry do slotu
+pub fn save_game(slot: usize, name: &str, mode: GameMode, world: &World) {
+    write_save(slot, &GameSave::from_world(world, name, mode));
+}
+fn write_save(slot: usize, save: &GameSave) {
+    let serialized = serde_json::to_string(save).unwrap();
+    fs::create_dir_all(SAVE_DIR).and_then(|_| fs::write(slot_path(slot), serialized)).unwrap_or(());
+}
+// Przeniesienie starego save.json do pierwszego wolnego slotu
+fn migrate_legacy_save() {
+    if fs::metadata(LEGACY_SAVE).is_ok() && fs::metadata(slot_path(0)).is_err() {
+        fs::create_dir_all(SAVE_DIR).and_then(|_| fs::rename(LEGACY_SAVE, slot_path(0))).unwrap_or(());
+    }
+}
+// Powód, dla którego zapisu nie da się wczytać
+#[derive(Debug)]
+pub enum LoadError {
+    NotFound,
+    Io(io::Error),
+    Corrupt(serde_json::Error),
+    TooNew(u32),
+}
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::NotFound => write!(f, "No save found!"),
+            LoadError::Io(e) => write!(f, "Could not read save: {}", e),
+            LoadError::Corrupt(e) => write!(f, "Save is damaged: {}", e),
+            LoadError::TooNew(v) => write!(f, "Save version {} is newer than this game (supports {})", v, SAVE_VERSION),
+        }
+    }
+}
+// Migracje: element i podnosi zapis z wersji i + 1 do i + 2
+const MIGRATIONS: &[fn(&mut Value)] = &[
+    // v1 -> v2: seed i stan generatora (stare zapisy kontynuują z seedem 0)
+    |save| {
+        save["seed"] = json!(0);
+        save["rng"] = Value::Null;
+    },
+    // v2 -> v3: metadane slotu
+    |save| {
+        save["name"] = json!(default_slot_name(0));
+        save["saved_at"] = json!(0);
+        save["play_time"] = json!(0.0);
+    },
+    // v3 -> v4: zakończone gry i statystyki zestrzeleń
+    |save| {
+        save["finished"] = json!(save["player_health"].as_i64().unwrap_or(0) <= 0);
+        save["kills"] = json!([0, 0, 0, 0]);
+    },
+    // v4 -> v5: liczniki względem zegara gry; stare wartości pochodziły z zegara procesu,
+    // więc odliczanie zaczyna się od nowa w chwili wczytania
+    |save| {
+        let now = save["play_time"].clone();
+        save["last_shot"] = now.clone();
+        save["last_power_up"] = now.clone();
+        for enemy in objects(save, "enemies") {
+            enemy["last_shot"] = now.clone();
+        }
+    },
+    // v5 -> v6: tryb gry (dla tabel wyników)
+    |save| {
+        save["mode"] = json!(GameMode::Normal);
+    },
+    // v6 -> v7: pozycje były w pikselach okna, które domyślnie miało 800x600
+    |save| {
+        save["arena"] = json!({"x": 800.0, "y": 600.0});
+    },
+    // v7 -> v8: id wrogów i przebicie pocisków (wcześniej każdy pocisk trafiał raz)
+    |save| {
+        for (id, enemy) in objects(save, "enemies").enumerate() {
+            enemy["id"] = json!(id);
+        }
+        for list in ["bullets", "enemy_bullets"] {
+            for bullet in objects(save, list) {
+                bullet["pierce"] = json!(1);
+                bullet["hit"] = json!([]);
+            }
+        }
+    },
+    // v8 -> v9: pociski wrogów mają własny rodzaj (wcześniej 0, jak zwykłe pociski gracza)
+    |save| {
+        for bullet in objects(save, "enemy_bullets") {
+            bullet["bullet_type"] = json!("Enemy");
+        }
+    },
+];
+// Obiekty z listy w zapisie; pozostałe elementy migracje pomijają (odrzuci je deserializacja)
+fn objects<'a>(save: &'a mut Value, list: &str) -> impl Iterator<Item = &'a mut Value> {
+    save.get_mut(list).and_then(Value::as_array_mut).into_iter().flatten().filter(|item| item.is_object())
+}
+fn damaged(reason: &str) -> LoadError {
+    LoadError::Corrupt(serde::de::Error::custom(reason))
+}
+// Podniesienie zapisu do aktualnej wersji; zapisy bez pola `version` to wersja 1
+fn migrate(mut save: Value) -> Result<Value, LoadError> {
+    if !save.is_object() {
+        return Err(damaged("expected an object"));
+    }
+    let version = save.get("version").map_or(Some(1), Value::as_u64).ok_or_else(|| damaged("invalid version"))?;
+    if version > SAVE_VERSION as u64 {
+        return Err(LoadError::TooNew(u32::try_from(version).unwrap_or(u32::MAX)));
+    }
+    let first = version.checked_sub(1).ok_or_else(|| damaged("invalid version 0"))? as usize;
+    for migration in &MIGRATIONS[first..] {
+        migration(&mut save);
+    }
+    save["version"] = json!(SAVE_VERSION);
+    Ok(save)
+}
+pub fn parse_save(data: &str) -> Result<GameSave, LoadError> {
+    let value = serde_json::from_str(data).map_err(LoadError::Corrupt)?;
+    serde_json::from_value(migrate(value)?).map_err(LoadError::Corrupt)
+}
+// Funkcja wczytywania gry ze slotu
+pub fn load_game(slot: usize) -> Result<GameSave, LoadError> {
+    match fs::read_to_string(slot_path(slot)) {
+        Ok(data) => parse_save(&data),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Err(LoadError::NotFound),
+        Err(e) => Err(LoadError::Io(e)),
+    }
+}
+// Zawartość wszystkich slotów
+pub fn list_slots() -> Vec<Result<GameSave, LoadError>> {
+    migrate_legacy_save();
+    (0..SLOT_COUNT).map(load_game).collect()
+}
+pub fn rename_slot(slot: usize, name: &str) -> Result<(), LoadError> {
+    let mut save = load_game(slot)?;
+    save.name = name.to_string();
+    write_save(slot, &save);
+    Ok(())
+}
+pub fn delete_slot(slot: usize) {
+    fs::remove_file(slot_path(slot)).unwrap_or(());
+}
+// Slot dla nowej gry: pierwszy pusty, a potem najdawniej zapisana zakończona gra. Gry w toku
+// i zapisy, których nie da się odczytać, nie są nadpisywane bez pytania - None, gdy nie ma miejsca
+pub fn slot_for_new_game() -> Option<usize> {
+    let slots: Vec<SlotUse> = list_slots().iter().map(SlotUse::of).collect();
+    pick_slot(&slots)
+}
+// Zawartość slotu z punktu widzenia nowej gry
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum SlotUse {
+    Empty,
+    Finished(u64), // chwila zapisu
+    Taken,
+}
+impl SlotUse {
+    fn of(slot: &Result<GameSave, LoadError>) -> SlotUse {
+        match slot {
+            Ok(save) if save.finished => SlotUse::Finished(save.saved_at),
+            Err(LoadError::NotFound) => SlotUse::Empty,
+            _ => SlotUse::Taken,
+        }
+    }
+}
+fn pick_slot(slots: &[SlotUse]) -> Option<usize> {
+    slots.iter().position(|&slot| slot == SlotUse::Empty).or_else(|| {
+        slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &slot)| match slot {
+                SlotUse::Finished(saved_at) => Some((saved_at, i)),
+                _ => None,
+            })
+            .min()
+            .map(|(_, i)| i)
+    })
+}
+// Funkcja zapisu ustawień
+pub fn save_settings(settings: &Settings) {
+    let serialized = serde_json::to_string(settings).unwrap();
+    fs::write("settings.json", serialized).unwrap_or(());
+}
+// Funkcja wczytywania ustawień
+pub fn load_settings() -> Settings {
+    if let Ok(data) = fs::read_to_string("settings.json") {
+        serde_json::from_str(&data).unwrap_or_default()
+    } else {
+        Settings::default()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Zapis w formacie sprzed wersjonowania
+    const V1_SAVE: &str = r#"{"score":120,"player_pos":{"x":10.0,"y":20.0},"difficulty":1.5,
+        "enemies":[{"pos":{"x":1.0,"y":2.0},"speed":100.0,"alive":true,"enemy_type":3,"health":2,"last_shot":4.5}],
+        "bullets":[{"pos":{"x":5.0,"y":5.0},"vel":{"x":0.0,"y":-400.0},"alive":true,"bullet_type":1}],
+        "enemy_bullets":[{"pos":{"x":8.0,"y":8.0},"vel":{"x":0.0,"y":200.0},"alive":true,"bullet_type":0}],
+        "power_ups":[],"player_health":2,"level":2,"shot_level":3}"#;
+
+    #[test]
+    fn migrates_unversioned_save() {
+        let save = parse_save(V1_SAVE).unwrap();
+        assert_eq!(save.version, SAVE_VERSION);
+        assert_eq!(save.name, "Slot 1");
+        assert_eq!(save.mode, GameMode::Normal);
+        assert_eq!(save.play_time, 0.0);
+        assert!(!save.finished);
+        assert_eq!(save.score, 120);
+        assert_eq!(save.shot_level, 3);
+        assert_eq!(save.enemies.len(), 1);
+        assert_eq!(save.enemies[0].last_shot, 0.0);
+        assert_eq!(save.enemies[0].id, 0);
+        assert_eq!((&save.enemies[0].enemy_type, &save.bullets[0].bullet_type), (&KindName::Index(3), &KindName::Index(1)));
+        assert_eq!(save.enemy_bullets[0].bullet_type, KindName::Name("Enemy".to_string()));
+        assert_eq!((save.bullets[0].pierce, save.bullets[0].hit.len()), (1, 0));
+        assert_eq!(save.seed, 0);
+        assert!(save.rng.is_none());
+    }
+
+    #[test]
+    fn current_save_roundtrips() {
+        let mut world = World::new(vec2(800.0, 600.0), 1.0, 5);
+        world.time = 42.0;
+        world.last_shot = 41.5;
+        let data = serde_json::to_string(&GameSave::from_world(&world, "Boss rush", GameMode::Daily)).unwrap();
+        let save = parse_save(&data).unwrap();
+        assert_eq!(save.name, "Boss rush");
+        assert_eq!(save.mode, GameMode::Daily);
+        assert_eq!(save.play_time, 42.0);
+        assert_eq!(save.seed, 5);
+        assert_eq!(save.rng, Some(world.rng.clone()));
+        let restored = save.into_world(vec2(800.0, 600.0));
+        assert_eq!(restored.time, 42.0);
+        assert_eq!(restored.last_shot, 41.5);
+    }
+
+    #[test]
+    fn kinds_are_saved_by_name() {
+        let mut world = World::new(vec2(480.0, 640.0), 1.0, 5);
+        let pos = vec2(50.0, 50.0);
+        world.enemies.spawn(Enemy { id: 0, pos, prev_pos: pos, speed: 1.0, kind: EnemyKind(2), health: 3, last_shot: 0.0 });
+        let data = serde_json::to_string(&GameSave::from_world(&world, "x", GameMode::Normal)).unwrap();
+        assert!(data.contains(r#""enemy_type":"Tank""#));
+        assert_eq!(parse_save(&data).unwrap().into_world(vec2(480.0, 640.0)).enemies.values().next().unwrap().kind, EnemyKind(2));
+        // Rodzaj usunięty z definicji - obiekt znika, reszta zapisu się wczytuje
+        let renamed = parse_save(&data.replace(r#""Tank""#, r#""Dreadnought""#)).unwrap();
+        assert!(renamed.into_world(vec2(480.0, 640.0)).enemies.is_empty());
+    }
+
+    #[test]
+    fn old_pixel_positions_are_rescaled() {
+        let save = parse_save(V1_SAVE).unwrap();
+        let world = save.into_world(vec2(480.0, 640.0));
+        assert_eq!(world.arena, vec2(480.0, 640.0));
+        assert!((world.player_pos - vec2(6.0, 20.0 * 640.0 / 600.0)).length() < 1e-4);
+        let enemy = world.enemies.values().next().unwrap();
+        assert!((enemy.pos - vec2(0.6, 2.0 * 640.0 / 600.0)).length() < 1e-4);
+        // Prędkości skalują się razem z planszą - wróg i pocisk przebywają ten sam ułamek planszy
+        assert!((enemy.speed - 100.0 * 640.0 / 600.0).abs() < 1e-3);
+        assert!((world.bullets.values().next().unwrap().vel - vec2(0.0, -400.0 * 640.0 / 600.0)).length() < 1e-3);
+        // Numery rodzajów ze starych zapisów wskazują wbudowane definicje
+        assert_eq!(world.defs.enemy(enemy.kind).name, "Shooter");
+        assert_eq!(world.defs.bullet(world.enemy_bullets.values().next().unwrap().kind).name, "Enemy");
+        let mut world = World::new(vec2(800.0, 600.0), 1.0, 5);
+        world.power_ups.spawn(PowerUp::new(vec2(400.0, 300.0), PowerUpKind(0), world.defs));
+        let save = parse_save(&serde_json::to_string(&GameSave::from_world(&world, "x", GameMode::Normal)).unwrap()).unwrap();
+        let power_up = save.into_world(vec2(480.0, 640.0)).power_ups.values().next().map(|p| (p.pos, p.speed)).unwrap();
+        assert!((power_up.0 - vec2(240.0, 320.0)).length() < 1e-3 && (power_up.1 - 80.0 * 640.0 / 600.0).abs() < 1e-3);
+        // Zapis w aktualnym formacie wraca bez zmian
+        let mut world = World::new(vec2(480.0, 640.0), 1.0, 5);
+        world.player_pos = vec2(123.0, 456.0);
+        let save = parse_save(&serde_json::to_string(&GameSave::from_world(&world, "x", GameMode::Normal)).unwrap()).unwrap();
+        assert_eq!(save.into_world(vec2(480.0, 640.0)).player_pos, vec2(123.0, 456.0));
+    }
+
+    #[test]
+    fn dead_run_is_saved_as_finished() {
+        let mut world = World::new(vec2(800.0, 600.0), 1.0, 5);
+        world.player_health = 0;
+        world.kills = vec![3, 0, 1, 0];
+        let save = parse_save(&serde_json::to_string(&GameSave::from_world(&world, "x", GameMode::Normal)).unwrap()).unwrap();
+        assert!(save.finished);
+        assert_eq!(save.kills, [3, 0, 1, 0]);
+        let legacy = V1_SAVE.replace(r#""player_health":2"#, r#""player_health":0"#);
+        assert!(parse_save(&legacy).unwrap().finished);
+    }
+
+    #[test]
+    fn new_game_prefers_empty_then_oldest_finished_slot() {
+        use SlotUse::*;
+        assert_eq!(pick_slot(&[Finished(5), Empty, Empty]), Some(1));
+        assert_eq!(pick_slot(&[Taken, Finished(9), Finished(3), Taken]), Some(2));
+        // Gry w toku, uszkodzone zapisy i zapisy nowszej wersji gry nie są wybierane
+        let slots = [parse_save(V1_SAVE), parse_save("{not json"), parse_save(r#"{"version":99}"#), Err(LoadError::NotFound)];
+        let slots: Vec<SlotUse> = slots.iter().map(SlotUse::of).collect();
+        assert_eq!(slots, [Taken, Taken, Taken, Empty]);
+        assert_eq!(pick_slot(&slots[..3]), None);
+    }
+
+    #[test]
+    fn reports_newer_and_damaged_saves() {
+        assert!(matches!(parse_save(r#"{"version":99}"#), Err(LoadError::TooNew(99))));
+        assert!(matches!(parse_save("{not json"), Err(LoadError::Corrupt(_))));
+        assert!(matches!(parse_save(r#"{"version":2,"score":"x"}"#), Err(LoadError::Corrupt(_))));
+        // Uszkodzona struktura nie może wywrócić gry w trakcie migracji
+        assert!(matches!(parse_save("[]"), Err(LoadError::Corrupt(_))));
+        assert!(matches!(parse_save(r#"{"version":4,"enemies":[1]}"#), Err(LoadError::Corrupt(_))));
+        assert!(matches!(parse_save(r#"{"version":0}"#), Err(LoadError::Corrupt(_))));
+        assert!(matches!(parse_save(r#"{"version":"9"}"#), Err(LoadError::Corrupt(_))));
+    }
+
+    #[test]
+    fn old_settings_keep_their_volume() {
+        let settings: Settings = serde_json::from_str(r#"{"difficulty":1.5,"sound_volume":0.3,"high_score":40}"#).unwrap();
+        assert_eq!(settings.master_volume, 0.3);
+        assert_eq!((settings.music_volume, settings.sfx_volume, settings.muted), (1.0, 1.0, false));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::rng::GameRng;
+use ::rand::Rng;
+// Prosty syntezator efektów w stylu sfxr - dźwięki powstają przy starcie gry, bez plików
+pub const SAMPLE_RATE: u32 = 22_050;
+// Kształt fali generatora
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Wave {
+    Square { duty: f32 }, // wypełnienie 0..1
+    Triangle,
+    Noise, // nowa losowa wartość co okres - wysokość szumu zależy od częstotliwości
+}
+// Obwiednia głośności: narastanie, podtrzymanie i wygaszanie (w sekundach)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Envelope {
+    pub attack: f32,
+    pub sustain: f32,
+    pub decay: f32,
+}
+impl Envelope {
+    pub fn duration(&self) -> f32 {
+        self.attack + self.sustain + self.decay
+    }
+    // Głośność 0..1 w chwili `t`
+    pub fn level(&self, t: f32) -> f32 {
+        if t < self.attack {
+            t / self.attack
+        } else if t < self.attack + self.sustain {
+            1.0
+        } else if t < self.duration() {
+            1.0 - (t - self.attack - self.sustain) / self.decay
+        } else {
+            0.0
+        }
+    }
+}
+// Parametry jednego dźwięku
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Voice {
+    pub wave: Wave,
+    pub start_hz: f32,
+    pub end_hz: f32, // częstotliwość na końcu - przesunięcie wykładnicze
+    pub envelope: Envelope,
+    pub volume: f32,
+}
+// Próbki -1..1 dla podanego głosu; szum jest powtarzalny (stały seed)
+pub fn synthesize(voice: &Voice) -> Vec<f32> {
+    let count = (voice.envelope.duration() * SAMPLE_RATE as f32).round() as usize;
+    let duration = voice.envelope.duration().max(f32::EPSILON);
+    let mut rng = GameRng::new(0x5FC);
+    let mut noise = rng.gen_range(-1.0..1.0);
+    let mut phase = 0.0f32;
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let hz = voice.start_hz * (voice.end_hz / voice.start_hz).powf(t / duration);
+            let sample = match voice.wave {
+                Wave::Square { duty } => if phase < duty { 1.0 } else { -1.0 },
+                Wave::Triangle => 4.0 * (phase - 0.5).abs() - 1.0,
+                Wave::Noise => noise,
+            };
+            phase += hz / SAMPLE_RATE as f32;
+            if phase >= 1.0 {
+                phase = phase.fract();
+                noise = rng.gen_range(-1.0..1.0);
+            }
+            sample * voice.envelope.level(t) * voice.volume
+        })
+        .collect()
+}
+// Zamiana na 16-bitowe PCM
+pub fn to_pcm(samples: &[f32]) -> Vec<i16> {
+    samples.iter().map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect()
+}
+// Bufor PCM jako plik WAV (mono) - w takiej postaci przyjmuje go macroquad
+pub fn wav(pcm: &[i16]) -> Vec<u8> {
+    let data_len = pcm.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for sample in pcm {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+    bytes
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLAT: Envelope = Envelope { attack: 0.0, sustain: 1.0, decay: 0.0 };
+
+    // Liczba przejść przez zero z ujemnej na dodatnią = liczba okresów
+    fn periods(samples: &[f32]) -> usize {
+        samples.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count()
+    }
+
+    #[test]
+    fn square_has_requested_frequency_and_length() {
+        let voice = Voice { wave: Wave::Square { duty: 0.5 }, start_hz: 440.0, end_hz: 440.0, envelope: FLAT, volume: 1.0 };
+        let samples = synthesize(&voice);
+        assert_eq!(samples.len(), SAMPLE_RATE as usize);
+        assert!((439..=441).contains(&periods(&samples)));
+        assert!(samples.iter().all(|s| s.abs() == 1.0));
+    }
+
+    #[test]
+    fn sweep_lowers_pitch() {
+        let voice = Voice { wave: Wave::Triangle, start_hz: 800.0, end_hz: 200.0, envelope: FLAT, volume: 1.0 };
+        let samples = synthesize(&voice);
+        let (first, second) = samples.split_at(samples.len() / 2);
+        assert!(periods(first) > periods(second) * 3 / 2);
+    }
+
+    #[test]
+    fn envelope_shapes_volume() {
+        let envelope = Envelope { attack: 0.1, sustain: 0.2, decay: 0.2 };
+        let voice = Voice { wave: Wave::Square { duty: 0.5 }, start_hz: 100.0, end_hz: 100.0, envelope, volume: 0.5 };
+        let samples = synthesize(&voice);
+        let at = |t: f32| samples[(t * SAMPLE_RATE as f32) as usize].abs();
+        assert_eq!(at(0.0), 0.0);
+        assert!((at(0.05) - 0.25).abs() < 0.01);
+        assert_eq!(at(0.2), 0.5);
+        assert!(at(0.499) < 0.01);
+    }
+
+    #[test]
+    fn noise_is_random_but_repeatable() {
+        let voice = Voice { wave: Wave::Noise, start_hz: 2_000.0, end_hz: 2_000.0, envelope: FLAT, volume: 1.0 };
+        let samples = synthesize(&voice);
+        assert_eq!(samples, synthesize(&voice));
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+        assert!(mean.abs() < 0.1);
+        assert!(samples.iter().any(|&s| s > 0.5) && samples.iter().any(|&s| s < -0.5));
+    }
+
+    #[test]
+    fn pcm_is_clamped_and_wrapped_in_wav() {
+        assert_eq!(to_pcm(&[0.0, 2.0, -2.0]), vec![0, i16::MAX, -i16::MAX]);
+        let bytes = wav(&[0, 1, 2]);
+        assert_eq!(&bytes[..4], b"RIFF");
+        assert_eq!(bytes.len(), 44 + 6);
+        assert_eq!(u32::from_le_bytes(bytes[40..44].try_into().unwrap()), 6);
+    }
+}
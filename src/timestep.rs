@@ -0,0 +1,60 @@
+// Stały krok symulacji (120 Hz) niezależny od częstotliwości odświeżania ekranu
+pub const TICK: f32 = 1.0 / 120.0;
+// Maksymalny czas klatki - po dłuższym przycięciu gra zwalnia zamiast nadrabiać setki kroków
+const MAX_FRAME_TIME: f64 = 0.25;
+// Akumulator czasu klatek zamieniający je na stałe kroki
+pub struct FixedStep {
+    accumulator: f64,
+}
+impl FixedStep {
+    pub fn new() -> FixedStep {
+        FixedStep { accumulator: 0.0 }
+    }
+    // Dodaje czas wyrenderowanej klatki
+    pub fn accumulate(&mut self, frame_time: f32) {
+        self.accumulator += (frame_time as f64).clamp(0.0, MAX_FRAME_TIME);
+    }
+    // Zwraca true, jeśli należy wykonać kolejny krok
+    pub fn tick(&mut self) -> bool {
+        if self.accumulator >= TICK as f64 {
+            self.accumulator -= TICK as f64;
+            true
+        } else {
+            false
+        }
+    }
+    // Ułamek kroku pozostały w akumulatorze - do interpolacji rysowania
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / TICK as f64) as f32
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticks(step: &mut FixedStep, frame_time: f32) -> u32 {
+        step.accumulate(frame_time);
+        let mut n = 0;
+        while step.tick() {
+            n += 1;
+        }
+        n
+    }
+
+    #[test]
+    fn same_ticks_regardless_of_frame_rate() {
+        let mut slow = FixedStep::new();
+        let mut fast = FixedStep::new();
+        let slow_ticks: u32 = (0..30).map(|_| ticks(&mut slow, 1.0 / 30.0)).sum();
+        let fast_ticks: u32 = (0..144).map(|_| ticks(&mut fast, 1.0 / 144.0)).sum();
+        assert!((119..=120).contains(&slow_ticks));
+        assert!((119..=120).contains(&fast_ticks));
+    }
+
+    #[test]
+    fn long_frames_are_clamped() {
+        let mut step = FixedStep::new();
+        assert!((29..=30).contains(&ticks(&mut step, 5.0)));
+        assert!(step.alpha() >= 0.0 && step.alpha() < 1.0);
+    }
+}
@@ -0,0 +1,458 @@
+use macroquad::prelude::*;
+use crate::canvas::PLAYFIELD;
+use crate::menu::MenuFocus;
+// Prosty interfejs w trybie natychmiastowym: widżety układane w pionowym stosie,
+// wyśrodkowane i mierzone według tekstu
+const TEXT_SIZE: f32 = 20.0;
+const TITLE_SIZE: f32 = 40.0;
+const DETAIL_SIZE: f32 = 16.0;
+const SPACING: f32 = 10.0;
+const PADDING: f32 = 10.0;
+const BUTTON_HEIGHT: f32 = 30.0;
+// Przyciski w stosie mają wspólną minimalną szerokość, żeby menu było równe
+const MIN_BUTTON_WIDTH: f32 = 120.0;
+const SLIDER_WIDTH: f32 = 320.0;
+const SLIDER_LABEL_WIDTH: f32 = 140.0;
+const INPUT_WIDTH: f32 = 320.0;
+const LIST_ROW_HEIGHT: f32 = 45.0;
+// Prostokąt na ekranie
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+impl Rect {
+    pub fn new(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect { x, y, w, h }
+    }
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.x && point.x < self.x + self.w && point.y >= self.y && point.y < self.y + self.h
+    }
+    // Położenie punktu w poziomie jako ułamek 0..1 szerokości
+    pub fn fraction_x(&self, point: Vec2) -> f32 {
+        ((point.x - self.x) / self.w).clamp(0.0, 1.0)
+    }
+}
+// Rysowanie i pomiar tekstu; w testach zastępowane atrapą
+pub trait Painter {
+    fn screen_width(&self) -> f32;
+    fn text_width(&self, text: &str, size: f32) -> f32;
+    fn rect(&mut self, rect: Rect, color: Color);
+    fn outline(&mut self, rect: Rect, color: Color);
+    // `baseline` jak w draw_text - dolna linia tekstu
+    fn text(&mut self, text: &str, x: f32, baseline: f32, size: f32, color: Color);
+}
+// Rysowanie przez macroquad na płótnie o logicznym rozmiarze
+pub struct Screen;
+impl Painter for Screen {
+    fn screen_width(&self) -> f32 {
+        PLAYFIELD.x
+    }
+    fn text_width(&self, text: &str, size: f32) -> f32 {
+        measure_text(text, None, size as u16, 1.0).width
+    }
+    fn rect(&mut self, rect: Rect, color: Color) {
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
+    }
+    fn outline(&mut self, rect: Rect, color: Color) {
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, color);
+    }
+    fn text(&mut self, text: &str, x: f32, baseline: f32, size: f32, color: Color) {
+        draw_text(text, x, baseline, size, color);
+    }
+}
+// Mysz i klawiatura w jednej klatce
+#[derive(Clone, Default)]
+pub struct UiInput {
+    pub mouse: Vec2,
+    pub clicked: bool,    // lewy przycisk wciśnięty w tej klatce
+    pub mouse_down: bool, // lewy przycisk przytrzymany
+    pub wheel: f32,       // dodatnie = w górę
+    pub chars: Vec<char>,
+    pub backspace: bool,
+    pub enter: bool,
+    pub escape: bool,
+}
+impl UiInput {
+    // Odczyt z macroquad; `mouse` w jednostkach płótna. Wpisane znaki są pobierane
+    // co klatkę, więc nie zalegają w kolejce
+    pub fn read(mouse: Vec2) -> UiInput {
+        let mut chars = vec![];
+        while let Some(c) = get_char_pressed() {
+            chars.push(c);
+        }
+        UiInput {
+            mouse,
+            clicked: is_mouse_button_pressed(MouseButton::Left),
+            mouse_down: is_mouse_button_down(MouseButton::Left),
+            wheel: mouse_wheel().1,
+            chars,
+            backspace: is_key_pressed(KeyCode::Backspace),
+            enter: is_key_pressed(KeyCode::Enter),
+            escape: is_key_pressed(KeyCode::Escape),
+        }
+    }
+}
+// Element wiersza
+pub enum Cell<'t> {
+    Label(&'t str, Color),
+    Button(&'t str),
+}
+// Wynik edycji tekstu w tej klatce
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TextEvent {
+    Editing,
+    Submitted,
+    Cancelled,
+}
+// Pozycja listy: tytuł i mniejszy opis pod nim
+pub struct ListItem {
+    pub title: String,
+    pub detail: String,
+    pub color: Color,
+}
+// Stan listy trzymany między klatkami
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct ListState {
+    pub selected: usize,
+    pub scroll: usize, // pierwsza widoczna pozycja
+}
+// Układ jednego ekranu w bieżącej klatce; widżety idą od góry, każdy wyśrodkowany
+pub struct Ui<'a> {
+    painter: &'a mut dyn Painter,
+    focus: &'a mut MenuFocus,
+    input: &'a UiInput,
+    y: f32,
+}
+impl<'a> Ui<'a> {
+    pub fn new(painter: &'a mut dyn Painter, focus: &'a mut MenuFocus, input: &'a UiInput, top: f32) -> Ui<'a> {
+        Ui { painter, focus, input, y: top }
+    }
+    pub fn space(&mut self, height: f32) {
+        self.y += height;
+    }
+    // Miejsce na widżet o podanym rozmiarze, wyśrodkowane w poziomie
+    fn allocate(&mut self, w: f32, h: f32) -> Rect {
+        let rect = Rect::new((self.painter.screen_width() - w) / 2.0, self.y, w, h);
+        self.y += h + SPACING;
+        rect
+    }
+    fn button_width(&self, text: &str) -> f32 {
+        self.painter.text_width(text, TEXT_SIZE) + 2.0 * PADDING
+    }
+    pub fn title(&mut self, text: &str) {
+        self.label_sized(text, TITLE_SIZE, WHITE);
+    }
+    pub fn label(&mut self, text: &str, color: Color) {
+        self.label_sized(text, TEXT_SIZE, color);
+    }
+    pub fn label_sized(&mut self, text: &str, size: f32, color: Color) {
+        let rect = self.allocate(self.painter.text_width(text, size), size);
+        self.painter.text(text, rect.x, rect.y + size * 0.8, size, color);
+    }
+    // Przycisk z fokusem: działa po kliknięciu albo Enter/Spacji/A, gdy ma fokus
+    pub fn button(&mut self, text: &str) -> bool {
+        let width = self.button_width(text).max(MIN_BUTTON_WIDTH);
+        let rect = self.allocate(width, BUTTON_HEIGHT);
+        self.button_in(text, rect)
+    }
+    fn button_in(&mut self, text: &str, rect: Rect) -> bool {
+        let id = self.focus.widget();
+        let hovered = rect.contains(self.input.mouse);
+        let clicked = hovered && self.input.clicked;
+        if clicked {
+            self.focus.focus(id);
+        }
+        let focused = self.focus.is_focused(id);
+        self.painter.rect(rect, if hovered || focused { GRAY } else { DARKGRAY });
+        if focused {
+            self.painter.outline(rect, YELLOW);
+        }
+        let text_x = rect.x + (rect.w - self.painter.text_width(text, TEXT_SIZE)) / 2.0;
+        self.painter.text(text, text_x, rect.y + 20.0, TEXT_SIZE, WHITE);
+        clicked || self.focus.activated(id)
+    }
+    // Wiersz elementów o naturalnej szerokości; zwraca numer użytego przycisku
+    pub fn row(&mut self, cells: &[Cell]) -> Option<usize> {
+        let widths: Vec<f32> = cells
+            .iter()
+            .map(|cell| match cell {
+                Cell::Label(text, _) => self.painter.text_width(text, TEXT_SIZE),
+                Cell::Button(text) => self.button_width(text),
+            })
+            .collect();
+        self.grid_row(cells, &widths)
+    }
+    // Wiersz o stałych szerokościach kolumn (tabele); etykiety są wyrównane do lewej
+    pub fn grid_row(&mut self, cells: &[Cell], widths: &[f32]) -> Option<usize> {
+        let has_button = cells.iter().any(|cell| matches!(cell, Cell::Button(_)));
+        let height = if has_button { BUTTON_HEIGHT } else { TEXT_SIZE };
+        let total = widths.iter().sum::<f32>() + SPACING * widths.len().saturating_sub(1) as f32;
+        let row = self.allocate(total, height);
+        let mut x = row.x;
+        let mut used = None;
+        for (i, (cell, &width)) in cells.iter().zip(widths).enumerate() {
+            let rect = Rect::new(x, row.y, width, height);
+            match cell {
+                Cell::Label(text, color) => self.painter.text(text, x, rect.y + height / 2.0 + 6.0, TEXT_SIZE, *color),
+                Cell::Button(text) => {
+                    if self.button_in(text, rect) {
+                        used = Some(i);
+                    }
+                }
+            }
+            x += width + SPACING;
+        }
+        used
+    }
+    // Suwak: lewo/prawo o `step`, kliknięcie albo przeciąganie po pasku; zwraca true przy zmianie
+    pub fn slider(&mut self, label: &str, value: &mut f32, min: f32, max: f32, step: f32) -> bool {
+        let id = self.focus.widget();
+        let row = self.allocate(SLIDER_WIDTH, BUTTON_HEIGHT);
+        let track = Rect::new(row.x + SLIDER_LABEL_WIDTH, row.y, row.w - SLIDER_LABEL_WIDTH, row.h);
+        let old = *value;
+        let over = track.contains(self.input.mouse);
+        if over && (self.input.clicked || self.input.mouse_down && self.focus.is_focused(id)) {
+            self.focus.focus(id);
+            *value = snap(min + track.fraction_x(self.input.mouse) * (max - min), min, step);
+        }
+        let delta = self.focus.adjust(id);
+        if delta != 0 {
+            *value = snap(*value + step * delta as f32, min, step);
+        }
+        *value = value.clamp(min, max);
+        let focused = self.focus.is_focused(id);
+        let color = if focused { YELLOW } else { WHITE };
+        self.painter.text(&format!("{}: {:.1}", label, value), row.x, row.y + 21.0, TEXT_SIZE, color);
+        let bar = Rect::new(track.x, track.y + track.h / 2.0 - 3.0, track.w, 6.0);
+        self.painter.rect(bar, DARKGRAY);
+        let fraction = if max > min { (*value - min) / (max - min) } else { 0.0 };
+        self.painter.rect(Rect::new(bar.x, bar.y, bar.w * fraction, bar.h), if focused { YELLOW } else { GRAY });
+        self.painter.rect(Rect::new(bar.x + bar.w * fraction - 4.0, track.y + 5.0, 8.0, track.h - 10.0), WHITE);
+        *value != old
+    }
+    // Przełącznik wł./wył.; zwraca true przy zmianie
+    pub fn toggle(&mut self, label: &str, value: &mut bool) -> bool {
+        let text = format!("{}: {}", label, if *value { "On" } else { "Off" });
+        let changed = self.button(&text);
+        if changed {
+            *value = !*value;
+        }
+        changed
+    }
+    // Pole tekstowe przejmujące klawiaturę: Enter zatwierdza, Escape anuluje
+    pub fn text_input(&mut self, label: &str, buffer: &mut String, max_len: usize) -> TextEvent {
+        for &c in &self.input.chars {
+            if !c.is_control() && buffer.chars().count() < max_len {
+                buffer.push(c);
+            }
+        }
+        if self.input.backspace {
+            buffer.pop();
+        }
+        let text = format!("{}: {}_", label, buffer);
+        let width = (self.painter.text_width(&text, TEXT_SIZE) + 2.0 * PADDING).max(INPUT_WIDTH);
+        let rect = self.allocate(width, BUTTON_HEIGHT);
+        self.painter.rect(rect, DARKGRAY);
+        self.painter.outline(rect, YELLOW);
+        self.painter.text(&text, rect.x + PADDING, rect.y + 21.0, TEXT_SIZE, WHITE);
+        if self.input.enter {
+            TextEvent::Submitted
+        } else if self.input.escape {
+            TextEvent::Cancelled
+        } else {
+            TextEvent::Editing
+        }
+    }
+    // Przewijana lista z zaznaczeniem; cała lista to jeden widżet. Góra/dół przesuwa
+    // zaznaczenie, a na krańcach przekazuje fokus dalej. Zwraca true po aktywacji pozycji
+    pub fn list(&mut self, items: &[ListItem], state: &mut ListState, rows: usize, width: f32) -> bool {
+        let id = self.focus.widget();
+        let visible = rows.min(items.len());
+        let area = self.allocate(width, visible as f32 * LIST_ROW_HEIGHT);
+        if items.is_empty() {
+            return false;
+        }
+        state.selected = state.selected.min(items.len() - 1);
+        let target = state.selected as i32 + self.focus.vertical(id);
+        if target != state.selected as i32 && (0..items.len() as i32).contains(&target) {
+            state.selected = target as usize;
+            self.focus.consume_vertical();
+            state.scroll = state.scroll.clamp((state.selected + 1).saturating_sub(visible), state.selected);
+        }
+        if area.contains(self.input.mouse) {
+            if self.input.wheel > 0.0 {
+                state.scroll = state.scroll.saturating_sub(1);
+            } else if self.input.wheel < 0.0 {
+                state.scroll += 1;
+            }
+        }
+        state.scroll = state.scroll.min(items.len() - visible);
+        for row in 0..visible {
+            let index = state.scroll + row;
+            let rect = Rect::new(area.x, area.y + row as f32 * LIST_ROW_HEIGHT, area.w, LIST_ROW_HEIGHT - 5.0);
+            if rect.contains(self.input.mouse) && self.input.clicked {
+                state.selected = index;
+                self.focus.focus(id);
+            }
+            if index == state.selected {
+                self.painter.rect(rect, DARKGRAY);
+                if self.focus.is_focused(id) {
+                    self.painter.outline(rect, YELLOW);
+                }
+            }
+            let item = &items[index];
+            self.painter.text(&item.title, rect.x + PADDING, rect.y + 20.0, TEXT_SIZE, item.color);
+            self.painter.text(&item.detail, rect.x + PADDING, rect.y + 38.0, DETAIL_SIZE, item.color);
+        }
+        // Pasek przewijania, gdy nie wszystko się mieści
+        if items.len() > visible {
+            let thumb_h = area.h * visible as f32 / items.len() as f32;
+            let thumb_y = area.y + area.h * state.scroll as f32 / items.len() as f32;
+            self.painter.rect(Rect::new(area.x + area.w + 4.0, thumb_y, 4.0, thumb_h), GRAY);
+        }
+        self.focus.activated(id)
+    }
+}
+// Zaokrąglenie do najbliższego kroku liczonego od `min`
+fn snap(value: f32, min: f32, step: f32) -> f32 {
+    min + ((value - min) / step).round() * step
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::menu::MenuInput;
+
+    // Atrapa: ekran 800 px, każdy znak ma 10 px; zapamiętuje narysowane teksty
+    #[derive(Default)]
+    struct FakePainter {
+        texts: Vec<(String, f32, f32)>,
+    }
+    impl Painter for FakePainter {
+        fn screen_width(&self) -> f32 {
+            800.0
+        }
+        fn text_width(&self, text: &str, _size: f32) -> f32 {
+            text.chars().count() as f32 * 10.0
+        }
+        fn rect(&mut self, _rect: Rect, _color: Color) {}
+        fn outline(&mut self, _rect: Rect, _color: Color) {}
+        fn text(&mut self, text: &str, x: f32, baseline: f32, _size: f32, _color: Color) {
+            self.texts.push((text.to_string(), x, baseline));
+        }
+    }
+
+    fn click(x: f32, y: f32) -> UiInput {
+        UiInput { mouse: vec2(x, y), clicked: true, mouse_down: true, ..UiInput::default() }
+    }
+
+    #[test]
+    fn buttons_are_centered_and_clickable() {
+        let (mut painter, mut focus) = (FakePainter::default(), MenuFocus::new());
+        // "Start" ma 50 px + marginesy, ale przycisk ma minimalną szerokość 120 px
+        let input = click(339.0, 115.0);
+        focus.begin(MenuInput::default());
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 100.0);
+        assert!(!ui.button("Start"));
+        let input = click(339.0, 155.0);
+        focus.begin(MenuInput::default());
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 100.0);
+        ui.button("Start");
+        assert!(ui.button("A much longer label"));
+        assert!(focus.is_focused(1));
+        let (_, x, _) = painter.texts.iter().find(|t| t.0 == "Start").unwrap();
+        assert_eq!(*x, 375.0);
+    }
+
+    #[test]
+    fn row_reports_clicked_button() {
+        let (mut painter, mut focus) = (FakePainter::default(), MenuFocus::new());
+        // Trzy przyciski po 30 px (1 znak + marginesy) i odstępy: 110 px od x = 345
+        let input = click(390.0, 10.0);
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 0.0);
+        assert_eq!(ui.row(&[Cell::Button("<"), Cell::Button("="), Cell::Button(">")]), Some(1));
+        let input = click(390.0, 10.0);
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 0.0);
+        assert_eq!(ui.row(&[Cell::Label("x", WHITE), Cell::Button("y")]), None);
+    }
+
+    #[test]
+    fn slider_follows_mouse_and_keys() {
+        let (mut painter, mut focus) = (FakePainter::default(), MenuFocus::new());
+        // Pasek zaczyna się 140 px za lewą krawędzią suwaka (x = 240) i ma 180 px
+        let input = click(240.0 + 140.0 + 90.0, 10.0);
+        let mut value = 0.0;
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 0.0);
+        assert!(ui.slider("Volume", &mut value, 0.0, 1.0, 0.1));
+        assert!((value - 0.5).abs() < 1e-6);
+        focus.begin(MenuInput { right: true, ..MenuInput::default() });
+        let input = UiInput::default();
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 0.0);
+        ui.slider("Volume", &mut value, 0.0, 1.0, 0.1);
+        assert!((value - 0.6).abs() < 1e-6);
+        // Wartość nie wychodzi poza zakres
+        value = 1.0;
+        focus.begin(MenuInput { right: true, ..MenuInput::default() });
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 0.0);
+        assert!(!ui.slider("Volume", &mut value, 0.0, 1.0, 0.1));
+    }
+
+    #[test]
+    fn list_scrolls_and_passes_focus_at_edges() {
+        let items: Vec<ListItem> = (0..5)
+            .map(|i| ListItem { title: i.to_string(), detail: String::new(), color: WHITE })
+            .collect();
+        let (mut painter, mut focus) = (FakePainter::default(), MenuFocus::new());
+        let mut state = ListState::default();
+        let input = UiInput::default();
+        let down = MenuInput { down: true, ..MenuInput::default() };
+        for expected in 1..=4 {
+            focus.begin(down);
+            let mut ui = Ui::new(&mut painter, &mut focus, &input, 0.0);
+            ui.list(&items, &mut state, 3, 400.0);
+            ui.button("Back");
+            focus.end();
+            assert_eq!(state.selected, expected);
+            assert!(focus.is_focused(0));
+        }
+        // Widać zaznaczoną pozycję
+        assert_eq!(state.scroll, 2);
+        // Na końcu listy ruch w dół przechodzi na następny widżet
+        focus.begin(down);
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 0.0);
+        ui.list(&items, &mut state, 3, 400.0);
+        ui.button("Back");
+        focus.end();
+        assert_eq!(state.selected, 4);
+        assert!(focus.is_focused(1));
+        // Kółko myszy przewija widok bez zmiany zaznaczenia
+        let input = UiInput { mouse: vec2(400.0, 10.0), wheel: 1.0, ..UiInput::default() };
+        focus.begin(MenuInput::default());
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 0.0);
+        ui.list(&items, &mut state, 3, 400.0);
+        assert_eq!((state.selected, state.scroll), (4, 1));
+        // Kliknięcie wybiera pozycję i daje liście fokus
+        let input = click(400.0, 50.0);
+        focus.begin(MenuInput::default());
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 0.0);
+        ui.list(&items, &mut state, 3, 400.0);
+        assert_eq!(state.selected, 2);
+        assert!(focus.is_focused(0));
+    }
+
+    #[test]
+    fn text_input_edits_and_submits() {
+        let (mut painter, mut focus) = (FakePainter::default(), MenuFocus::new());
+        let mut name = "Ac".to_string();
+        let input = UiInput { chars: vec!['e', '\r', 'x'], ..UiInput::default() };
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 0.0);
+        assert_eq!(ui.text_input("Name", &mut name, 4), TextEvent::Editing);
+        assert_eq!(name, "Acex");
+        let input = UiInput { backspace: true, enter: true, ..UiInput::default() };
+        let mut ui = Ui::new(&mut painter, &mut focus, &input, 0.0);
+        assert_eq!(ui.text_input("Name", &mut name, 4), TextEvent::Submitted);
+        assert_eq!(name, "Ace");
+    }
+}
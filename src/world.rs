@@ -0,0 +1,705 @@
+use macroquad::prelude::{vec2, Vec2};
+use ::rand::Rng;
+use crate::collision::{self, Shape, SpatialHash, PLAYER_SIZE};
+use crate::defs::{self, Definitions, Effect, Explosion, Firing, Movement};
+use crate::entity::{Handle, Pool};
+use crate::kinds::{BulletKind, EnemyKind, PowerUpKind};
+use crate::rng::GameRng;
+// Domyślnie pocisk trafia jednego wroga i znika
+pub const DEFAULT_PIERCE: u32 = 1;
+// Bok komórki siatki kolizji - dwa razy większy od wroga
+const GRID_CELL: f32 = 40.0;
+// Do levelu 20 przerwa między strzałami wrogów maleje do 1/3 wartości z definicji
+const LEVEL_FIRE_SPEEDUP: f64 = 2.0 / 3.0;
+// Obiekty w siatce kolizji (uchwyty do obiektów świata)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Body {
+    Enemy(Handle),
+    EnemyBullet(Handle),
+    PowerUp(Handle),
+}
+// Struktura wroga
+pub struct Enemy {
+    pub id: u32, // niepowtarzalny w obrębie gry - pociski pamiętają, kogo już trafiły
+    pub pos: Vec2,
+    pub prev_pos: Vec2, // pozycja z poprzedniego kroku (interpolacja)
+    pub speed: f32,
+    pub kind: EnemyKind,
+    pub health: i32,
+    pub last_shot: f64, // czas gry
+}
+impl Enemy {
+    pub fn shape(&self, defs: &Definitions) -> Shape {
+        collision::enemy(self.pos, defs.enemy(self.kind).size())
+    }
+}
+// Struktura pocisku
+pub struct Bullet {
+    pub pos: Vec2,
+    pub prev_pos: Vec2,
+    pub vel: Vec2,
+    pub kind: BulletKind,
+    pub pierce: u32, // ilu jeszcze wrogów może trafić
+    pub hit: Vec<u32>, // id wrogów już trafionych (przelatując przez wroga, trafia go raz)
+}
+impl Bullet {
+    pub fn new(pos: Vec2, vel: Vec2, kind: BulletKind) -> Bullet {
+        Bullet { pos, prev_pos: pos, vel, kind, pierce: DEFAULT_PIERCE, hit: vec![] }
+    }
+    pub fn shape(&self, defs: &Definitions) -> Shape {
+        collision::bullet(self.pos, defs.bullet(self.kind).radius)
+    }
+}
+// Struktura power-up
+pub struct PowerUp {
+    pub pos: Vec2,
+    pub prev_pos: Vec2,
+    pub speed: f32,
+    pub kind: PowerUpKind,
+}
+impl PowerUp {
+    pub fn new(pos: Vec2, kind: PowerUpKind, defs: &Definitions) -> PowerUp {
+        PowerUp { pos, prev_pos: pos, speed: defs.power_up(kind).speed, kind }
+    }
+    pub fn shape(&self, defs: &Definitions) -> Shape {
+        collision::power_up(self.pos, defs.power_up(self.kind).radius)
+    }
+}
+// Stan wejścia w jednej klatce (niezależny od klawiatury)
+#[derive(Clone, Copy, Default)]
+pub struct Input {
+    pub left: bool,
+    pub right: bool,
+    pub up: bool,
+    pub down: bool,
+    pub fire: bool,
+}
+// Zdarzenia z kroków symulacji (np. dla dźwięku) - odbierane przez `take_events`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameEvent {
+    PlayerShot,
+    EnemyShot,
+    EnemyHit,
+    EnemyKilled(EnemyKind, Explosion), // z dźwiękiem z definicji świata
+    PowerUpCollected(PowerUpKind),
+    PlayerDamaged,
+}
+// Stan symulacji gry - bez rysowania i bez okna
+pub struct World {
+    pub arena: Vec2,
+    pub difficulty: f32,
+    pub player_pos: Vec2,
+    pub prev_player_pos: Vec2,
+    pub player_health: i32,
+    pub score: i32,
+    // Obiekty usunięte w trakcie kroku znikają od razu, a ich sloty są zwalniane na końcu kroku
+    pub enemies: Pool<Enemy>,
+    pub bullets: Pool<Bullet>,
+    pub enemy_bullets: Pool<Bullet>,
+    pub power_ups: Pool<PowerUp>,
+    pub level: u32,
+    pub shot_level: u32,
+    pub last_shot: f64, // czas gry ostatniego strzału
+    pub last_power_up: f64,
+    pub seed: u64,
+    pub rng: GameRng,
+    pub time: f64, // zegar gry - płynie tylko w krokach symulacji
+    pub kills: Vec<u32>, // zestrzeleni wrogowie według rodzaju
+    pub next_enemy_id: u32,
+    pub events: Vec<GameEvent>,
+    pub defs: &'static Definitions, // rodzaje wrogów, pocisków i power-upów
+    grid: SpatialHash<Body>, // budowana od nowa w każdym kroku, po ruchu obiektów
+}
+impl World {
+    // Nowa gra na arenie o podanym rozmiarze; wszystkie losowania wynikają z `seed`
+    pub fn new(arena: Vec2, difficulty: f32, seed: u64) -> World {
+        let player_pos = vec2((arena.x - PLAYER_SIZE.x) / 2.0, arena.y - 50.0);
+        let defs = defs::current();
+        World {
+            arena,
+            difficulty,
+            player_pos,
+            prev_player_pos: player_pos,
+            player_health: 3,
+            score: 0,
+            enemies: Pool::new(),
+            bullets: Pool::new(),
+            enemy_bullets: Pool::new(),
+            power_ups: Pool::new(),
+            level: 1,
+            shot_level: 1,
+            last_shot: 0.0,
+            last_power_up: 0.0,
+            seed,
+            rng: GameRng::new(seed),
+            time: 0.0,
+            kills: vec![0; defs.enemies.len()],
+            next_enemy_id: 0,
+            events: vec![],
+            defs,
+            grid: SpatialHash::new(GRID_CELL),
+        }
+    }
+    pub fn is_over(&self) -> bool {
+        self.player_health <= 0
+    }
+    // Zdarzenia zebrane od ostatniego wywołania
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+    // Jeden krok symulacji o długości `dt`; wszystkie liczniki korzystają z zegara gry
+    pub fn step(&mut self, input: &Input, dt: f32) {
+        let arena = self.arena;
+        let level = self.level;
+        let defs = self.defs;
+        self.remember_positions();
+        self.time += dt as f64;
+        let time = self.time;
+        // Sterowanie graczem
+        if input.left {
+            self.player_pos.x -= 300.0 * dt;
+        }
+        if input.right {
+            self.player_pos.x += 300.0 * dt;
+        }
+        if input.up {
+            self.player_pos.y -= 300.0 * dt;
+        }
+        if input.down {
+            self.player_pos.y += 300.0 * dt;
+        }
+        // Ograniczenie ruchu gracza
+        self.player_pos = self.player_pos.clamp(Vec2::ZERO, arena - PLAYER_SIZE);
+        // Strzelanie
+        if input.fire && time - self.last_shot > 0.2 {
+            let bullet_vel = vec2(0.0, -400.0);
+            let offset_step = 10.0;
+            let start_offset = -((self.shot_level - 1) as f32 * offset_step / 2.0);
+            let kind = if self.shot_level > 1 { defs.power_bullet.kind() } else { defs.player_bullet.kind() };
+            for i in 0..self.shot_level {
+                let offset = start_offset + (i as f32) * offset_step;
+                let pos = collision::muzzle(self.player_pos) + vec2(offset, 0.0);
+                let mut bullet = Bullet::new(pos, bullet_vel, kind);
+                bullet.pierce = defs.bullet(kind).pierce;
+                self.bullets.spawn(bullet);
+            }
+            self.last_shot = time;
+            self.events.push(GameEvent::PlayerShot);
+        }
+        // Spawn wrogów
+        let level_factor = (level as f32 / 5.0) + 1.0;
+        // Szanse dobrane dla 60 klatek na sekundę - skalowane długością kroku
+        let frames = dt * 60.0;
+        let spawn_chance = 0.02 * self.difficulty * level_factor * frames;
+        if self.rng.gen_range(0.0..1.0) < spawn_chance {
+            if let Some(index) = pick_weighted(&mut self.rng, defs.enemies.iter().map(|def| def.spawn_weight)) {
+                let kind = EnemyKind(index);
+                let def = defs.enemy(kind);
+                let speed = def.speed * self.difficulty * (level as f32 / 10.0 + 1.0);
+                let pos = vec2(self.rng.gen_range(0.0..arena.x - def.width), -def.height);
+                self.enemies.spawn(Enemy {
+                    id: self.next_enemy_id,
+                    pos,
+                    prev_pos: pos,
+                    speed,
+                    kind,
+                    health: def.health,
+                    last_shot: if def.firing != Firing::None { time } else { 0.0 },
+                });
+                self.next_enemy_id += 1;
+            }
+        }
+        // Spawn power-upów
+        if time - self.last_power_up > 10.0 && self.rng.gen_range(0.0..1.0) < 0.005 * frames {
+            if let Some(index) = pick_weighted(&mut self.rng, defs.power_ups.iter().map(|def| def.spawn_weight)) {
+                let kind = PowerUpKind(index);
+                let radius = defs.power_up(kind).radius;
+                let pos = vec2(self.rng.gen_range(radius..arena.x - radius), -radius);
+                self.power_ups.spawn(PowerUp::new(pos, kind, defs));
+                self.last_power_up = time;
+            }
+        }
+        // Aktualizacja pocisków
+        for bullet in self.bullets.values_mut().chain(self.enemy_bullets.values_mut()) {
+            bullet.pos += bullet.vel * dt;
+        }
+        // Aktualizacja wrogów
+        for enemy in self.enemies.values_mut() {
+            let def = defs.enemy(enemy.kind);
+            enemy.pos.y += enemy.speed * dt;
+            if let Movement::Weave { amplitude, period } = def.movement {
+                // Prędkość pozioma jako pochodna sinusa - wrogi tego rodzaju kołyszą się razem
+                let omega = std::f32::consts::TAU / period;
+                let sway = amplitude * omega * (omega * time as f32).cos() * dt;
+                enemy.pos.x = (enemy.pos.x + sway).clamp(0.0, (arena.x - def.width).max(0.0));
+            }
+            let Firing::Aimed { interval, bullet, speed, count, spread } = &def.firing else { continue };
+            if time - enemy.last_shot > interval * (1.0 - LEVEL_FIRE_SPEEDUP * (level as f64 / 20.0).min(1.0)) {
+                // Pociski lecą ze środka wroga w stronę środka gracza, dodatkowe rozłożone wachlarzem
+                let pos = enemy.shape(defs).center();
+                let aim = (collision::player(self.player_pos).center() - pos).normalize_or_zero();
+                let bullet_speed = speed * self.difficulty * (level as f32 / 10.0 + 1.0);
+                let kind = bullet.kind();
+                for i in 0..*count {
+                    let angle = (i as f32 - (*count - 1) as f32 / 2.0) * spread.to_radians();
+                    let direction = if angle == 0.0 { aim } else { Vec2::from_angle(angle).rotate(aim) };
+                    self.enemy_bullets.spawn(Bullet::new(pos, direction * bullet_speed, kind));
+                }
+                enemy.last_shot = time;
+                self.events.push(GameEvent::EnemyShot);
+            }
+        }
+        // Aktualizacja power-upów
+        for power_up in self.power_ups.values_mut() {
+            power_up.pos.y += power_up.speed * dt;
+        }
+        self.rebuild_grid();
+        // Kolizje pocisków gracza z wrogami
+        self.resolve_bullet_hits();
+        // Kolizje pocisków wrogów z graczem
+        let mut bullet_damage = 0;
+        let player = collision::player(self.player_pos);
+        let near_player = self.grid.query(player);
+        for &body in &near_player {
+            if let Body::EnemyBullet(handle) = body {
+                let Some(bullet) = self.enemy_bullets.get(handle) else { continue };
+                if player.overlaps(bullet.shape(defs)) {
+                    bullet_damage += defs.bullet(bullet.kind).damage;
+                    self.enemy_bullets.despawn(handle);
+                }
+            }
+        }
+        if bullet_damage > 0 {
+            self.player_health -= bullet_damage;
+            self.events.push(GameEvent::PlayerDamaged);
+        }
+        // Kolizje gracza z wrogami
+        let mut player_hit = false;
+        for &body in &near_player {
+            if let Body::Enemy(handle) = body {
+                let Some(enemy) = self.enemies.get(handle) else { continue };
+                if player.overlaps(enemy.shape(defs)) {
+                    self.enemies.despawn(handle);
+                    player_hit = true;
+                }
+            }
+        }
+        if player_hit {
+            self.player_health -= 1;
+            self.events.push(GameEvent::PlayerDamaged);
+        }
+        // Kolizje gracza z power-upami
+        for &body in &near_player {
+            if let Body::PowerUp(handle) = body {
+                let Some(power_up) = self.power_ups.get(handle) else { continue };
+                if player.overlaps(power_up.shape(defs)) {
+                    let kind = power_up.kind;
+                    self.power_ups.despawn(handle);
+                    self.events.push(GameEvent::PowerUpCollected(kind));
+                    match defs.power_up(kind).effect {
+                        Effect::Heal { amount, max } => self.player_health = (self.player_health + amount).min(max),
+                        Effect::ExtraShot { amount, max } => self.shot_level = (self.shot_level + amount).min(max),
+                    }
+                }
+            }
+        }
+        // Postęp levelu
+        if self.score > (self.level as i32 * 100) {
+            self.level += 1;
+        }
+        // Obiekty, które opuściły arenę
+        let outside = |pos: Vec2| pos.y < 0.0 || pos.y > arena.y || pos.x < 0.0 || pos.x > arena.x;
+        self.bullets.despawn_where(|b| outside(b.pos));
+        self.enemy_bullets.despawn_where(|b| outside(b.pos));
+        self.enemies.despawn_where(|e| e.pos.y > arena.y);
+        self.power_ups.despawn_where(|p| p.pos.y > arena.y);
+        // Zwolnienie slotów usuniętych w tym kroku
+        self.bullets.flush();
+        self.enemy_bullets.flush();
+        self.enemies.flush();
+        self.power_ups.flush();
+    }
+    // Siatka kolizji z aktualnymi pozycjami wrogów, ich pocisków i power-upów
+    fn rebuild_grid(&mut self) {
+        self.grid.clear();
+        for (handle, enemy) in self.enemies.iter() {
+            self.grid.insert(Body::Enemy(handle), enemy.shape(self.defs));
+        }
+        for (handle, bullet) in self.enemy_bullets.iter() {
+            self.grid.insert(Body::EnemyBullet(handle), bullet.shape(self.defs));
+        }
+        for (handle, power_up) in self.power_ups.iter() {
+            self.grid.insert(Body::PowerUp(handle), power_up.shape(self.defs));
+        }
+    }
+    // Trafienia pocisków gracza: każdy pocisk trafia najbliższych wrogów, najwyżej tylu, ile
+    // wynosi jego `pierce`; wróg zabity wcześniej w tym kroku nie zatrzymuje kolejnych pocisków
+    fn resolve_bullet_hits(&mut self) {
+        let World { bullets, enemies, power_ups, score, kills, events, grid, rng, defs, .. } = self;
+        let defs: &Definitions = defs;
+        for bullet in bullets.values_mut() {
+            let shape = bullet.shape(defs);
+            let mut targets: Vec<Handle> = grid
+                .query(shape)
+                .into_iter()
+                .filter_map(|body| match body {
+                    Body::Enemy(handle) => Some(handle),
+                    _ => None,
+                })
+                .filter(|&handle| {
+                    enemies.get(handle).is_some_and(|enemy| !bullet.hit.contains(&enemy.id) && shape.overlaps(enemy.shape(defs)))
+                })
+                .collect();
+            let distance = |handle| enemies.get(handle).map_or(0.0, |enemy| enemy.shape(defs).center().distance_squared(bullet.pos));
+            targets.sort_by(|&a, &b| distance(a).total_cmp(&distance(b)));
+            for handle in targets.into_iter().take(bullet.pierce as usize) {
+                let Some(enemy) = enemies.get_mut(handle) else { continue };
+                enemy.health -= defs.bullet(bullet.kind).damage;
+                bullet.pierce -= 1;
+                bullet.hit.push(enemy.id);
+                if enemy.health <= 0 {
+                    let kind = enemy.kind;
+                    let center = enemy.shape(defs).center();
+                    enemies.despawn(handle);
+                    let def = defs.enemy(kind);
+                    *score += def.score;
+                    if let Some(kills) = kills.get_mut(kind.0) {
+                        *kills += 1;
+                    }
+                    events.push(GameEvent::EnemyKilled(kind, def.explosion));
+                    // Z tabeli zrzutów wypada najwyżej jeden power-up
+                    for drop in &def.drops {
+                        if rng.gen_range(0.0..1.0) < drop.chance {
+                            power_ups.spawn(PowerUp::new(center, drop.power_up.kind(), defs));
+                            break;
+                        }
+                    }
+                } else {
+                    events.push(GameEvent::EnemyHit);
+                }
+            }
+        }
+        // Pocisk, który wyczerpał przebicie, znika
+        bullets.despawn_where(|b| b.pierce == 0);
+    }
+    // Zapamiętanie pozycji przed krokiem, żeby rysować stan pośredni między krokami
+    fn remember_positions(&mut self) {
+        self.prev_player_pos = self.player_pos;
+        for enemy in self.enemies.values_mut() {
+            enemy.prev_pos = enemy.pos;
+        }
+        for bullet in self.bullets.values_mut().chain(self.enemy_bullets.values_mut()) {
+            bullet.prev_pos = bullet.pos;
+        }
+        for power_up in self.power_ups.values_mut() {
+            power_up.prev_pos = power_up.pos;
+        }
+    }
+}
+// Losowanie indeksu według wag
+fn pick_weighted(rng: &mut GameRng, weights: impl Iterator<Item = u32> + Clone) -> Option<usize> {
+    let total: u32 = weights.clone().sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.gen_range(0..total);
+    for (index, weight) in weights.enumerate() {
+        if roll < weight {
+            return Some(index);
+        }
+        roll -= weight;
+    }
+    None
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs::{Drop, EnemyDef, KindRef};
+
+    const ARENA: Vec2 = Vec2::new(800.0, 600.0);
+    const DT: f32 = 1.0 / 60.0;
+    // Rodzaje z wbudowanego zestawu definicji
+    const NORMAL: EnemyKind = EnemyKind(0);
+    const FAST: EnemyKind = EnemyKind(1);
+    const TANK: EnemyKind = EnemyKind(2);
+    const NORMAL_BULLET: BulletKind = BulletKind(0);
+    const POWER_BULLET: BulletKind = BulletKind(1);
+    const ENEMY_BULLET: BulletKind = BulletKind(2);
+
+    // Nieruchomy wróg z pełnym zdrowiem dla swojego typu
+    fn enemy(id: u32, kind: EnemyKind, pos: Vec2) -> Enemy {
+        Enemy { id, pos, prev_pos: pos, speed: 0.0, kind, health: defs::current().enemy(kind).health, last_shot: 0.0 }
+    }
+
+    #[test]
+    fn runs_many_frames_headless() {
+        let mut world = World::new(ARENA, 1.0, 7);
+        let input = Input { left: true, fire: true, ..Input::default() };
+        for frame in 0..10_000 {
+            if world.is_over() {
+                world = World::new(ARENA, 1.0, frame);
+            }
+            world.step(&input, DT);
+            assert!(world.player_pos.x >= 0.0 && world.player_pos.x <= ARENA.x - 20.0);
+            assert!(world.player_pos.y >= 0.0 && world.player_pos.y <= ARENA.y - 20.0);
+        }
+    }
+
+    #[test]
+    fn same_seed_replays_identically() {
+        let run = |seed| {
+            let mut world = World::new(ARENA, 1.5, seed);
+            let input = Input { fire: true, ..Input::default() };
+            let mut spawns = vec![];
+            for _ in 0..2_000 {
+                world.step(&input, DT);
+                spawns.extend(world.enemies.values().map(|e| (e.kind, e.pos.x.to_bits())));
+            }
+            (spawns, world.score, world.player_health)
+        };
+        assert!(run(123) == run(123));
+        assert!(run(123) != run(321));
+    }
+
+    #[test]
+    fn player_is_clamped_to_arena() {
+        let mut world = World::new(ARENA, 1.0, 7);
+        let input = Input { right: true, down: true, ..Input::default() };
+        world.step(&input, 10.0);
+        assert_eq!(world.player_pos, vec2(ARENA.x - 20.0, ARENA.y - 20.0));
+    }
+
+    #[test]
+    fn fire_spawns_one_bullet_per_shot_level() {
+        let mut world = World::new(ARENA, 0.0, 7);
+        world.shot_level = 3;
+        world.time = 1.0;
+        let input = Input { fire: true, ..Input::default() };
+        world.step(&input, DT);
+        assert_eq!(world.bullets.len(), 3);
+        assert!(world.bullets.values().all(|b| b.kind == POWER_BULLET));
+        // Cooldown nie pozwala strzelić w kolejnej klatce
+        world.step(&input, DT);
+        assert_eq!(world.bullets.len(), 3);
+        // ...ale po 0.2 s czasu gry już tak
+        for _ in 0..12 {
+            world.step(&Input::default(), DT);
+        }
+        world.step(&input, DT);
+        assert_eq!(world.bullets.len(), 6);
+    }
+
+    #[test]
+    fn hits_match_drawn_enemy_square() {
+        let bullet = |pos| Bullet::new(pos, Vec2::ZERO, NORMAL_BULLET);
+        // Pocisk przy prawym dolnym rogu rysowanego kwadratu trafia...
+        let mut world = World::new(ARENA, 0.0, 7);
+        world.enemies.spawn(enemy(0, NORMAL, vec2(100.0, 100.0)));
+        world.bullets.spawn(bullet(vec2(123.0, 118.0)));
+        world.step(&Input::default(), DT);
+        assert!(world.enemies.is_empty());
+        // ...a pocisk obok lewego górnego rogu już nie
+        let mut world = World::new(ARENA, 0.0, 7);
+        world.enemies.spawn(enemy(0, NORMAL, vec2(100.0, 100.0)));
+        world.bullets.spawn(bullet(vec2(90.0, 90.0)));
+        world.step(&Input::default(), DT);
+        assert_eq!(world.enemies.len(), 1);
+    }
+
+    #[test]
+    fn bullet_kills_enemy_and_scores() {
+        let mut world = World::new(ARENA, 0.0, 7);
+        world.enemies.spawn(enemy(0, FAST, vec2(100.0, 100.0)));
+        world.bullets.spawn(Bullet::new(vec2(105.0, 105.0), Vec2::ZERO, NORMAL_BULLET));
+        world.step(&Input::default(), DT);
+        assert!(world.enemies.is_empty());
+        assert!(world.bullets.is_empty());
+        assert_eq!(world.score, 20);
+        assert_eq!(world.kills, [0, 1, 0, 0]);
+        assert_eq!(world.take_events(), vec![GameEvent::EnemyKilled(FAST, Explosion::Sharp)]);
+        assert!(world.events.is_empty());
+    }
+
+    #[test]
+    fn bullet_hits_only_the_closest_of_stacked_enemies() {
+        let mut world = World::new(ARENA, 0.0, 7);
+        world.enemies.spawn(enemy(0, NORMAL, vec2(100.0, 100.0)));
+        world.enemies.spawn(enemy(1, NORMAL, vec2(104.0, 104.0)));
+        world.bullets.spawn(Bullet::new(vec2(115.0, 115.0), Vec2::ZERO, NORMAL_BULLET));
+        world.step(&Input::default(), DT);
+        assert_eq!(world.enemies.len(), 1);
+        assert_eq!(world.enemies.values().next().unwrap().id, 0);
+        assert!(world.bullets.is_empty());
+        assert_eq!((world.score, world.kills[0]), (10, 1));
+    }
+
+    #[test]
+    fn damaging_bullet_stops_at_first_enemy() {
+        let mut world = World::new(ARENA, 0.0, 7);
+        world.enemies.spawn(enemy(0, NORMAL, vec2(110.0, 100.0)));
+        world.enemies.spawn(enemy(1, TANK, vec2(100.0, 100.0)));
+        world.bullets.spawn(Bullet::new(vec2(112.0, 110.0), Vec2::ZERO, NORMAL_BULLET));
+        world.step(&Input::default(), DT);
+        // Tank jest bliżej: traci 1 punkt zdrowia, zwykły wróg obok zostaje nietknięty
+        assert_eq!(world.enemies.values().map(|e| e.health).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(world.bullets.is_empty());
+        assert_eq!(world.take_events(), vec![GameEvent::EnemyHit]);
+    }
+
+    #[test]
+    fn piercing_bullet_hits_each_enemy_once() {
+        let mut world = World::new(ARENA, 0.0, 7);
+        for (id, y) in [(0, 100.0), (1, 104.0), (2, 108.0)] {
+            world.enemies.spawn(enemy(id, TANK, vec2(100.0, y)));
+        }
+        let mut bullet = Bullet::new(vec2(110.0, 110.0), vec2(0.0, -60.0), POWER_BULLET);
+        bullet.pierce = 2;
+        world.bullets.spawn(bullet);
+        world.step(&Input::default(), DT);
+        // Wzmocniony pocisk zadaje 2 obrażenia dwóm najbliższym wrogom
+        assert_eq!(world.enemies.values().map(|e| e.health).collect::<Vec<_>>(), vec![1, 1, 3]);
+        assert!(world.bullets.is_empty());
+        // Pocisk z zapasem przebicia leci dalej i nie trafia drugi raz tego samego wroga
+        let mut world = World::new(ARENA, 0.0, 7);
+        world.enemies.spawn(enemy(0, TANK, vec2(100.0, 100.0)));
+        let mut bullet = Bullet::new(vec2(110.0, 119.0), vec2(0.0, -60.0), NORMAL_BULLET);
+        bullet.pierce = 3;
+        world.bullets.spawn(bullet);
+        for _ in 0..10 {
+            world.step(&Input::default(), DT);
+        }
+        assert_eq!(world.enemies.values().next().unwrap().health, 2);
+        assert_eq!(world.bullets.values().next().unwrap().pierce, 2);
+    }
+
+    #[test]
+    fn killed_enemy_handle_stays_invalid_after_slot_reuse() {
+        let mut world = World::new(ARENA, 0.0, 7);
+        let target = world.enemies.spawn(enemy(0, NORMAL, vec2(100.0, 100.0)));
+        world.bullets.spawn(Bullet::new(vec2(105.0, 105.0), Vec2::ZERO, NORMAL_BULLET));
+        world.step(&Input::default(), DT);
+        assert_eq!(world.enemies.get(target).map(|e| e.id), None);
+        // Nowy wróg dostaje zwolniony slot, ale stary uchwyt go nie wskazuje
+        let next = world.enemies.spawn(enemy(1, NORMAL, vec2(300.0, 300.0)));
+        assert!(world.enemies.get(target).is_none());
+        assert_eq!(world.enemies.get(next).map(|e| e.id), Some(1));
+    }
+
+    // Świat z definicjami zmienionymi względem wbudowanych
+    fn world_with(change: impl FnOnce(&mut Definitions)) -> World {
+        let mut defs = Definitions::default();
+        change(&mut defs);
+        assert_eq!(defs.validate(), Vec::<String>::new());
+        defs.resolve();
+        let mut world = World::new(ARENA, 0.0, 7);
+        world.defs = Box::leak(Box::new(defs));
+        world.kills = vec![0; world.defs.enemies.len()];
+        world
+    }
+
+    #[test]
+    fn killed_enemy_drops_power_up_from_table() {
+        let mut world = world_with(|defs| defs.enemies[0].drops.push(Drop { power_up: KindRef::new("MultiShot"), chance: 1.0 }));
+        world.enemies.spawn(enemy(0, NORMAL, vec2(100.0, 100.0)));
+        world.bullets.spawn(Bullet::new(vec2(105.0, 105.0), Vec2::ZERO, NORMAL_BULLET));
+        world.step(&Input::default(), DT);
+        let power_up = world.power_ups.values().next().unwrap();
+        assert_eq!(world.defs.power_up(power_up.kind).name, "MultiShot");
+        assert_eq!(power_up.pos, vec2(110.0, 110.0));
+    }
+
+    #[test]
+    fn new_enemy_kind_weaves_and_fires_a_spread() {
+        let mut world = world_with(|defs| {
+            let gunship = EnemyDef {
+                name: "Gunship".to_string(),
+                movement: Movement::Weave { amplitude: 30.0, period: 2.0 },
+                firing: Firing::Aimed { interval: 1.0, bullet: KindRef::new("Enemy"), speed: 100.0, count: 3, spread: 20.0 },
+                ..defs.enemies[0].clone()
+            };
+            defs.enemies.push(gunship);
+        });
+        world.difficulty = 1.0;
+        world.time = 2.0;
+        let handle = world.enemies.spawn(Enemy { kind: EnemyKind(4), ..enemy(0, NORMAL, vec2(200.0, 100.0)) });
+        world.step(&Input::default(), DT);
+        let gunship = world.enemies.get(handle).unwrap();
+        let aim = (collision::player(world.player_pos).center() - gunship.shape(world.defs).center()).normalize();
+        let directions: Vec<Vec2> = world.enemy_bullets.values().map(|b| b.vel.normalize()).collect();
+        assert_eq!(directions.len(), 3);
+        assert!((directions[1] - aim).length() < 1e-5);
+        assert!((directions[0].angle_between(directions[1]).to_degrees().abs() - 20.0).abs() < 1e-3);
+        // Kołysanie przesuwa wroga na boki, ale nie dalej niż dwie amplitudy od startu
+        // Bez dalszych losowych wrogów
+        world.difficulty = 0.0;
+        let mut xs = vec![];
+        for _ in 0..240 {
+            world.step(&Input::default(), DT);
+            xs.push(world.enemies.get(handle).unwrap().pos.x);
+        }
+        let (min, max) = xs.iter().fold((f32::MAX, f32::MIN), |(min, max), &x| (min.min(x), max.max(x)));
+        assert!(max - min > 30.0 && min > 140.0 && max < 260.0, "{} {}", min, max);
+    }
+
+    #[test]
+    fn enemy_bullets_deal_their_damage() {
+        let mut world = world_with(|defs| defs.bullets[2].damage = 2);
+        world.player_health = 5;
+        let center = collision::player(world.player_pos).center();
+        world.enemy_bullets.spawn(Bullet::new(center, Vec2::ZERO, ENEMY_BULLET));
+        world.enemy_bullets.spawn(Bullet::new(center, Vec2::ZERO, NORMAL_BULLET));
+        world.step(&Input::default(), DT);
+        // Oba trafienia w jednym kroku się sumują, ale to jedno zdarzenie
+        assert_eq!(world.player_health, 2);
+        assert_eq!(world.take_events(), vec![GameEvent::PlayerDamaged]);
+        assert!(world.enemy_bullets.is_empty());
+    }
+
+    #[test]
+    fn short_fire_interval_stays_positive_on_high_levels() {
+        let mut world = world_with(|defs| {
+            defs.enemies[3].firing = Firing::Aimed { interval: 0.5, bullet: KindRef::new("Enemy"), speed: 100.0, count: 1, spread: 0.0 };
+        });
+        world.level = 40;
+        world.enemies.spawn(enemy(0, EnemyKind(3), vec2(200.0, 100.0)));
+        let mut shots = 0;
+        for _ in 0..60 {
+            world.step(&Input::default(), DT);
+            shots += world.take_events().iter().filter(|&&event| event == GameEvent::EnemyShot).count();
+        }
+        // Sekunda gry przy przerwie 0.5 / 3
+        assert_eq!(shots, 6);
+    }
+
+    // Pomiar czasu kroku dla tysięcy obiektów bez okna:
+    // cargo test --release world_step_benchmark -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn world_step_benchmark() {
+        let mut rng = GameRng::new(1);
+        let mut world = World::new(ARENA, 0.0, 1);
+        for id in 0..2_000 {
+            let mut e = enemy(id, TANK, vec2(rng.gen_range(0.0..ARENA.x - 20.0), rng.gen_range(0.0..ARENA.y - 100.0)));
+            e.health = i32::MAX;
+            world.enemies.spawn(e);
+        }
+        for _ in 0..3_000 {
+            let mut bullet = Bullet::new(vec2(rng.gen_range(0.0..ARENA.x), rng.gen_range(0.0..ARENA.y - 100.0)), Vec2::ZERO, NORMAL_BULLET);
+            bullet.pierce = u32::MAX;
+            world.bullets.spawn(bullet);
+        }
+        for _ in 0..1_000 {
+            let pos = vec2(rng.gen_range(0.0..ARENA.x), rng.gen_range(0.0..ARENA.y - 100.0));
+            world.enemy_bullets.spawn(Bullet::new(pos, Vec2::ZERO, ENEMY_BULLET));
+        }
+        let steps = 200;
+        let start = std::time::Instant::now();
+        for _ in 0..steps {
+            world.step(&Input::default(), DT);
+        }
+        let per_step = start.elapsed() / steps;
+        let objects = world.enemies.len() + world.bullets.len() + world.enemy_bullets.len();
+        println!("{} objects: {:?} per step", objects, per_step);
+        assert_eq!(world.enemies.len(), 2_000);
+    }
+}